@@ -0,0 +1,36 @@
+//! Benchmarks parsing and solving each day separately, against the inputs in `src/input`.
+//! Every day does all of its parsing in `Solution::parse`, so the part benchmarks only measure
+//! solving.
+//! Run a single day with e.g. `cargo bench --bench days -- day18/`.
+use adventofcode_2015::input::get_input::get_input;
+use adventofcode_2015::DAYS;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+use std::time::Duration;
+
+fn bench_days(c: &mut Criterion) {
+    for day in &DAYS {
+        let input = get_input(day.input).unwrap();
+        let solution = day.parse(&input).unwrap();
+
+        let mut group = c.benchmark_group(format!("day{:02}", day.day));
+        group.bench_function("parse", |b| {
+            b.iter(|| day.parse(black_box(&input)).unwrap())
+        });
+        group.bench_function("part1", |b| b.iter(|| black_box(&solution).part1()));
+        group.bench_function("part2", |b| b.iter(|| black_box(&solution).part2()));
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // Some parts take hundreds of milliseconds (e.g. day 4 part 2), so keep the sample count at
+    // criterion's minimum.
+    config = Criterion::default()
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(2));
+    targets = bench_days
+}
+criterion_main!(benches);
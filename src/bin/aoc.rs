@@ -0,0 +1,285 @@
+use adventofcode_2015::day3::{Delivery, VisitMap};
+use adventofcode_2015::day5::RuleSet;
+use adventofcode_2015::input::fetch::Fetcher;
+use adventofcode_2015::input::get_input::{
+    find_input_dir, get_input_from, input_dir, input_path, resolve_input_dir,
+};
+use adventofcode_2015::run_all::{self, run_all};
+use adventofcode_2015::verify::{report, verify, Status};
+use adventofcode_2015::{get_day, Day, DAYS};
+use std::error::Error;
+use std::fs::{read_to_string, File};
+use std::io::{self, BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+
+const USAGE: &str = "Usage:
+  aoc run --day <1-25> [--part <1|2>] [--input <path|->] [--input-dir <path>]
+  aoc run-all [--threads <n>] [--input-dir <path>] [--format <table|json>]
+  aoc verify [--input-dir <path>]
+  aoc fetch [--day <1-25>]
+  aoc render [--santas <n>] [--format <ascii|pgm|ppm>] [--input <path|->] [--out <path>]
+  aoc classify --rules <path> [--input <path|->]";
+
+struct RunArgs {
+    day: u8,
+    /// Run both parts if unspecified.
+    part: Option<u8>,
+    /// An input file, or `-` for stdin.
+    input: Option<String>,
+    /// Overrides `AOC_INPUT_DIR`, and is resolved the same way.
+    input_dir: Option<PathBuf>,
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, Box<dyn Error>> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut input_dir = None;
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(value.parse::<u8>()?),
+            "--part" | "-p" => part = Some(value.parse::<u8>()?),
+            "--input" | "-i" => input = Some(value),
+            "--input-dir" => input_dir = Some(resolve_input_dir(Some(Path::new(&value)))),
+            _ => return Err(format!("Unknown argument `{}`", arg).into()),
+        }
+    }
+    Ok(RunArgs {
+        day: day.ok_or("Missing `--day`")?,
+        part,
+        input,
+        input_dir,
+    })
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let day = get_day(args.day).ok_or_else(|| format!("Day {} does not exist", args.day))?;
+    let (input, input_path) = match (args.input, args.input_dir) {
+        (Some(path), _) if path == "-" => {
+            (get_input_from(Path::new("-"), day.input)?, "stdin".into())
+        }
+        (Some(path), _) => (read_to_string(&path)?, path),
+        (None, dir) => {
+            let dir = dir.unwrap_or_else(|| find_input_dir(day.input));
+            let path = input_path(&dir, day.input).display().to_string();
+            (get_input_from(&dir, day.input)?, path)
+        }
+    };
+
+    let start = Instant::now();
+    let solution = day
+        .parse(&input)
+        .map_err(|e| format!("{}: {}", input_path, e))?;
+    println!("Day {} parse: ({:.2?})", day.day, start.elapsed());
+
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    for part in parts {
+        let start = Instant::now();
+        let answer = match part {
+            1 => solution.part1(),
+            2 => solution.part2(),
+            _ => return Err(format!("Part {} does not exist", part).into()),
+        };
+        println!(
+            "Day {} part {}: {} ({:.2?})",
+            day.day,
+            part,
+            answer,
+            start.elapsed()
+        );
+    }
+    Ok(())
+}
+
+/// Run every day concurrently, on one thread per core unless `--threads` is given.
+fn run_all_days(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut threads = 0;
+    let mut input_dir = None;
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+        match arg.as_str() {
+            "--threads" | "-t" => threads = value.parse()?,
+            "--input-dir" => input_dir = Some(resolve_input_dir(Some(Path::new(&value)))),
+            "--format" => {
+                json = match value.as_str() {
+                    "table" => false,
+                    "json" => true,
+                    _ => return Err(format!("Unknown format `{}`", value).into()),
+                }
+            }
+            _ => return Err(format!("Unknown argument `{}`", arg).into()),
+        }
+    }
+    let days: Vec<_> = DAYS.iter().collect();
+    let result = run_all(&days, input_dir.as_deref(), threads)?;
+    if json {
+        println!("{}", run_all::to_json(&result));
+    } else {
+        println!("{}", run_all::report(&result));
+    }
+    if result.runs.iter().any(|run| run.error.is_some()) {
+        return Err("Some days could not be run".into());
+    }
+    Ok(())
+}
+
+/// Check every day against the `answers.toml` in the input directory.
+fn run_verify(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let dir = match (args.next().as_deref(), args.next()) {
+        (None, _) => input_dir(),
+        (Some("--input-dir"), Some(dir)) => resolve_input_dir(Some(Path::new(&dir))),
+        (Some(arg), _) => return Err(format!("Unexpected argument `{}`", arg).into()),
+    };
+    let results = verify(&dir)?;
+    println!("{}", report(&results));
+    if results
+        .iter()
+        .any(|result| matches!(result.status(), Status::Fail | Status::Error))
+    {
+        return Err("Some answers are wrong or missing".into());
+    }
+    Ok(())
+}
+
+/// Download inputs with the session in `AOC_SESSION`, every day if `--day` is omitted.
+fn run_fetch(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let days: Vec<_> = match (args.next().as_deref(), args.next()) {
+        (None, _) => DAYS.iter().collect(),
+        (Some("--day" | "-d"), Some(day)) => {
+            let day = day.parse::<u8>()?;
+            vec![get_day(day).ok_or_else(|| format!("Day {} does not exist", day))?]
+        }
+        (Some(arg), _) => return Err(format!("Unexpected argument `{}`", arg).into()),
+    };
+    let fetcher = Fetcher::from_env()?;
+    for day in days {
+        fetcher.fetch(day)?;
+    }
+    println!("Inputs cached in {}", fetcher.cache_dir().display());
+    Ok(())
+}
+
+/// Read an input file, stdin for `-`, or else the day's input as found by `get_input`.
+fn read_day_input(day: &Day, input: Option<String>) -> Result<String, Box<dyn Error>> {
+    match input {
+        Some(path) if path == "-" => get_input_from(Path::new("-"), day.input),
+        Some(path) => Ok(read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?),
+        None => get_input_from(&find_input_dir(day.input), day.input),
+    }
+}
+
+/// Render the houses visited on day 3 as an ASCII map or a PGM/PPM heatmap, to stdout unless
+/// `--out` is given.
+fn run_render(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut santas = NonZeroUsize::MIN;
+    let mut format = "ascii".to_string();
+    let mut input = None;
+    let mut out = None;
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+        match arg.as_str() {
+            "--santas" | "-s" => {
+                santas = NonZeroUsize::new(value.parse()?)
+                    .ok_or("A delivery needs at least one Santa")?
+            }
+            "--format" => format = value,
+            "--input" | "-i" => input = Some(value),
+            "--out" | "-o" => out = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown argument `{}`", arg).into()),
+        }
+    }
+    if !["ascii", "pgm", "ppm"].contains(&format.as_str()) {
+        return Err(format!("Unknown format `{}`", format).into());
+    }
+
+    let directions = read_day_input(get_day(3).unwrap(), input)?;
+    let map = VisitMap::new(Delivery::new(santas).visits(&directions));
+
+    let mut writer: Box<dyn Write> = match &out {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        )),
+        None => Box::new(io::stdout().lock()),
+    };
+    match format.as_str() {
+        "ascii" => writer.write_all(map.to_ascii().as_bytes())?,
+        "pgm" => map.write_pgm(&mut writer)?,
+        _ => map.write_ppm(&mut writer)?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Classify day 5 strings with the rules in a file, counting the first rule each naughty string
+/// breaks.
+fn run_classify(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut rules = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+        match arg.as_str() {
+            "--rules" | "-r" => rules = Some(value),
+            "--input" | "-i" => input = Some(value),
+            _ => return Err(format!("Unknown argument `{}`", arg).into()),
+        }
+    }
+    let rules_path = rules.ok_or("Missing `--rules`")?;
+    let rules: RuleSet = read_to_string(&rules_path)
+        .map_err(|e| format!("{}: {}", rules_path, e))?
+        .parse()
+        .map_err(|e| format!("{}: {}", rules_path, e))?;
+    let strings = read_day_input(get_day(5).unwrap(), input)?;
+
+    let mut nice = 0;
+    let mut broken = vec![0; rules.rules().len()];
+    for string in strings.lines() {
+        match rules.check(string) {
+            Ok(()) => nice += 1,
+            Err(rule) => {
+                let i = rules.rules().iter().position(|r| r == rule).unwrap();
+                broken[i] += 1;
+            }
+        }
+    }
+    println!("{} nice, {} naughty", nice, broken.iter().sum::<usize>());
+    for (rule, count) in rules.rules().iter().zip(broken) {
+        println!("{:>8}  {}", count, rule);
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("run") => parse_run_args(args).and_then(run),
+        Some("run-all") => run_all_days(args),
+        Some("verify") => run_verify(args),
+        Some("fetch") => run_fetch(args),
+        Some("render") => run_render(args),
+        Some("classify") => run_classify(args),
+        _ => Err(USAGE.into()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Stable fallbacks for slice APIs that have fast paths on nightly, enabled with the `nightly`
+//! feature.
+
+/// Overlapping windows of length `N`, like `slice::array_windows`.
+#[cfg(feature = "nightly")]
+#[inline(always)]
+pub(crate) fn array_windows<T, const N: usize>(slice: &[T]) -> impl Iterator<Item = &[T; N]> {
+    slice.array_windows::<N>()
+}
+
+/// Overlapping windows of length `N`, like `slice::array_windows`.
+#[cfg(not(feature = "nightly"))]
+#[inline(always)]
+pub(crate) fn array_windows<T, const N: usize>(slice: &[T]) -> impl Iterator<Item = &[T; N]> {
+    slice
+        .windows(N)
+        .map(|window| window.try_into().unwrap_or_else(|_| unreachable!()))
+}
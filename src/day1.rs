@@ -1,30 +1,233 @@
-#[allow(dead_code)]
-fn find_floor(directions: &str) -> i32 {
-    let mut floor = 0;
-    for c in directions.bytes() {
-        match c {
-            b'(' => floor += 1,
-            b')' => floor -= 1,
-            _ => (),
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::iter::Enumerate;
+use std::str::Bytes;
+
+/// Iterates over the floor Santa is on after each instruction, as (1-based position, floor).
+/// Bytes other than parentheses are skipped.
+#[derive(Clone)]
+pub struct FloorTrace<'a> {
+    directions: Enumerate<Bytes<'a>>,
+    floor: i32,
+}
+
+impl<'a> FloorTrace<'a> {
+    pub fn new(directions: &'a str) -> Self {
+        Self {
+            directions: directions.bytes().enumerate(),
+            floor: 0,
+        }
+    }
+}
+
+impl Iterator for FloorTrace<'_> {
+    type Item = (usize, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, c) in self.directions.by_ref() {
+            match c {
+                b'(' => self.floor += 1,
+                b')' => self.floor -= 1,
+                _ => continue,
+            }
+            return Some((i + 1, self.floor));
         }
+        None
     }
-    floor
+}
+
+/// Statistics over a whole trace. The starting floor 0 counts as reached.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FloorStats {
+    pub floor: i32,
+    pub max_floor: i32,
+    pub min_floor: i32,
+    /// Positions of instructions that take Santa from the ground floor into the basement.
+    pub basement_entries: Vec<usize>,
+    /// Positions of instructions that take Santa from the basement back to the ground floor.
+    pub basement_exits: Vec<usize>,
+    /// Number of instructions after which Santa is on each floor.
+    pub time_per_floor: BTreeMap<i32, usize>,
+}
+
+impl FloorStats {
+    pub fn new(trace: FloorTrace) -> Self {
+        let mut stats = Self::default();
+        for (position, floor) in trace {
+            match (stats.floor, floor) {
+                (0, -1) => stats.basement_entries.push(position),
+                (-1, 0) => stats.basement_exits.push(position),
+                _ => (),
+            }
+            stats.floor = floor;
+            stats.max_floor = stats.max_floor.max(floor);
+            stats.min_floor = stats.min_floor.min(floor);
+            *stats.time_per_floor.entry(floor).or_default() += 1;
+        }
+        stats
+    }
+}
+
+#[allow(dead_code)]
+fn find_floor(directions: &str) -> i32 {
+    FloorTrace::new(directions)
+        .last()
+        .map_or(0, |(_, floor)| floor)
 }
 
 #[allow(dead_code)]
 fn find_basement_pos(directions: &str) -> Option<usize> {
-    let mut floor = 0;
-    for (i, c) in directions.bytes().enumerate() {
-        match c {
-            b'(' => floor += 1,
-            b')' => floor -= 1,
-            _ => (),
+    FloorTrace::new(directions)
+        .find(|&(_, floor)| floor == -1)
+        .map(|(position, _)| position)
+}
+
+/// Bytes counted at a time by the chunked counters.
+const CHUNK: usize = 64;
+
+/// Net floor change over a chunk, using SIMD comparisons.
+#[cfg(feature = "nightly")]
+#[inline(always)]
+fn count_chunk(chunk: &[u8; CHUNK]) -> i64 {
+    use std::simd::cmp::SimdPartialEq;
+    use std::simd::u8x64;
+
+    let bytes = u8x64::from_array(*chunk);
+    let up = bytes.simd_eq(u8x64::splat(b'(')).to_bitmask().count_ones();
+    let down = bytes.simd_eq(u8x64::splat(b')')).to_bitmask().count_ones();
+    up as i64 - down as i64
+}
+
+/// Net floor change over a chunk. The loop is branchless and the counts fit in a u8, so LLVM
+/// vectorizes it.
+#[cfg(not(feature = "nightly"))]
+#[inline(always)]
+fn count_chunk(chunk: &[u8; CHUNK]) -> i64 {
+    let (mut up, mut down) = (0u8, 0u8);
+    for &c in chunk {
+        up += (c == b'(') as u8;
+        down += (c == b')') as u8;
+    }
+    up as i64 - down as i64
+}
+
+/// Net floor change and the lowest floor reached relative to the start of the chunk (at most 0).
+/// This is a scalar pass, so it's only worth it near the ground floor.
+#[inline(always)]
+fn chunk_prefix_min(chunk: &[u8; CHUNK]) -> (i64, i64) {
+    let (mut floor, mut min) = (0i32, 0i32);
+    for &c in chunk {
+        floor += (c == b'(') as i32 - (c == b')') as i32;
+        min = min.min(floor);
+    }
+    (floor as i64, min as i64)
+}
+
+/// Split into whole chunks and the remaining bytes.
+fn chunks(directions: &[u8]) -> (impl Iterator<Item = &[u8; CHUNK]>, &[u8]) {
+    let chunks = directions.chunks_exact(CHUNK);
+    let remainder = chunks.remainder();
+    let chunks = chunks.map(|chunk| chunk.try_into().unwrap_or_else(|_| unreachable!()));
+    (chunks, remainder)
+}
+
+/// Chunked equivalent of `find_floor`, which doesn't overflow on huge inputs.
+fn count_floor(directions: &[u8]) -> i64 {
+    let (chunks, remainder) = chunks(directions);
+    chunks.map(count_chunk).sum::<i64>() + find_floor_scalar(remainder)
+}
+
+fn find_floor_scalar(directions: &[u8]) -> i64 {
+    directions
+        .iter()
+        .map(|&c| (c == b'(') as i64 - (c == b')') as i64)
+        .sum()
+}
+
+/// Follows directions fed in pieces of any size, e.g. from a stream, while looking for the
+/// basement. A chunk can only go down `CHUNK` floors, so chunks starting higher than that are
+/// counted without looking at individual instructions. Closer to the ground floor, each chunk's
+/// prefix minimum decides whether to step through it.
+#[derive(Debug, Default)]
+pub struct FloorCounter {
+    pub floor: i64,
+    /// Number of bytes fed so far.
+    pub position: usize,
+    pub basement_pos: Option<usize>,
+}
+
+impl FloorCounter {
+    pub fn feed(&mut self, directions: &[u8]) {
+        let (chunks, remainder) = chunks(directions);
+        for chunk in chunks {
+            if self.basement_pos.is_some() || self.floor >= CHUNK as i64 {
+                self.floor += count_chunk(chunk);
+                self.position += CHUNK;
+                continue;
+            }
+            let (delta, min) = chunk_prefix_min(chunk);
+            if self.floor + min < 0 {
+                self.feed_scalar(chunk);
+            } else {
+                self.floor += delta;
+                self.position += CHUNK;
+            }
+        }
+        self.feed_scalar(remainder);
+    }
+
+    fn feed_scalar(&mut self, directions: &[u8]) {
+        for &c in directions {
+            self.floor += (c == b'(') as i64 - (c == b')') as i64;
+            self.position += 1;
+            if self.floor == -1 && self.basement_pos.is_none() {
+                self.basement_pos = Some(self.position);
+            }
         }
-        if floor == -1 {
-            return Some(i + 1);
+    }
+
+    /// Feed a whole stream, returning the final floor and the basement position.
+    pub fn from_reader(mut reader: impl Read) -> io::Result<Self> {
+        let mut counter = Self::default();
+        let mut buffer = vec![0; 1 << 16];
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => return Ok(counter),
+                Ok(n) => counter.feed(&buffer[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
         }
     }
-    None
+}
+
+/// Chunked equivalent of `find_basement_pos`.
+fn find_basement_pos_chunked(directions: &[u8]) -> Option<usize> {
+    let mut counter = FloorCounter::default();
+    counter.feed(directions);
+    counter.basement_pos
+}
+
+pub struct Day1 {
+    directions: String,
+}
+
+impl Solution for Day1 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            directions: input.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        count_floor(self.directions.as_bytes()).into()
+    }
+
+    fn part2(&self) -> Answer {
+        find_basement_pos_chunked(self.directions.as_bytes()).into()
+    }
 }
 
 #[cfg(test)]
@@ -41,4 +244,80 @@ mod solution {
         let basement_pos = find_basement_pos(&directions).unwrap();
         assert_eq!(basement_pos, 1783);
     }
+
+    #[test]
+    fn floor_trace() {
+        let trace: Vec<_> = FloorTrace::new("(()\n))").collect();
+        assert_eq!(trace, [(1, 1), (2, 2), (3, 1), (5, 0), (6, -1)]);
+    }
+
+    #[test]
+    fn floor_stats() {
+        let stats = FloorStats::new(FloorTrace::new("()))(()(((\n"));
+        assert_eq!(stats.floor, 2);
+        assert_eq!(stats.max_floor, 2);
+        assert_eq!(stats.min_floor, -2);
+        assert_eq!(stats.basement_entries, [3, 7]);
+        assert_eq!(stats.basement_exits, [6, 8]);
+        assert_eq!(
+            stats.time_per_floor,
+            BTreeMap::from([(-2, 1), (-1, 3), (0, 3), (1, 2), (2, 1)])
+        );
+
+        let stats = FloorStats::new(FloorTrace::new(&get_input("directions").unwrap()));
+        assert_eq!(stats.floor, 232);
+        assert_eq!(stats.basement_entries[0], 1783);
+        assert_eq!(stats.time_per_floor.values().sum::<usize>(), 7000);
+    }
+
+    /// Pseudo-random directions which wander around the ground floor.
+    fn random_directions(len: usize, seed: u64) -> String {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                match state >> 61 {
+                    0..=3 => '(',
+                    4..=6 => ')',
+                    _ => '\n',
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunked_matches_scalar() {
+        let mut inputs = vec![get_input("directions").unwrap(), String::new(), ")".into()];
+        // Climbs well above the ground floor before going down to the basement
+        inputs.push("(".repeat(300) + &")".repeat(301));
+        inputs.extend((0..20).map(|seed| random_directions(1000 + seed as usize * 37, seed)));
+        for directions in &inputs {
+            assert_eq!(
+                count_floor(directions.as_bytes()),
+                find_floor(directions) as i64
+            );
+            assert_eq!(
+                find_basement_pos_chunked(directions.as_bytes()),
+                find_basement_pos(directions)
+            );
+        }
+    }
+
+    #[test]
+    fn floor_counter_streaming() {
+        let directions = get_input("directions").unwrap();
+        for piece in [1, 7, 64, 100] {
+            let mut counter = FloorCounter::default();
+            for bytes in directions.as_bytes().chunks(piece) {
+                counter.feed(bytes);
+            }
+            assert_eq!((counter.floor, counter.basement_pos), (232, Some(1783)));
+        }
+
+        let counter = FloorCounter::from_reader(directions.as_bytes()).unwrap();
+        assert_eq!((counter.floor, counter.basement_pos), (232, Some(1783)));
+        assert_eq!(counter.position, directions.len());
+    }
 }
@@ -1,6 +1,5 @@
-#[allow(dead_code)]
-const INPUT: &str = "1113122113";
-
+use crate::parse::{Line, ParseError};
+use crate::solution::{Answer, Solution};
 #[allow(dead_code)]
 fn look_and_say(sequence: &str) -> String {
     let mut output = String::new();
@@ -19,32 +18,62 @@ fn look_and_say(sequence: &str) -> String {
     output
 }
 
+fn look_and_say_length(sequence: &str, iterations: usize) -> usize {
+    let mut output_sequence = String::from(sequence);
+    for _ in 0..iterations {
+        output_sequence = look_and_say(&output_sequence);
+    }
+    output_sequence.len()
+}
+
+pub struct Day10 {
+    sequence: String,
+}
+
+impl Solution for Day10 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let line = Line::single(input.trim());
+        if line.text.is_empty() {
+            return Err(line.error("", "expected a sequence of digits"));
+        }
+        if let Some(c) = line.text.matches(|c: char| !c.is_ascii_digit()).next() {
+            return Err(line.error(c, "expected a digit"));
+        }
+        Ok(Self {
+            sequence: line.text.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        look_and_say_length(&self.sequence, 40).into()
+    }
+
+    fn part2(&self) -> Answer {
+        look_and_say_length(&self.sequence, 50).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn test_look_and_say_simple() {
         let input = "111221";
-        let output_sequence = look_and_say(&input);
+        let output_sequence = look_and_say(input);
         assert_eq!(output_sequence, "312211");
     }
 
     #[test]
     fn get_length_look_and_say40() {
-        let mut output_sequence = look_and_say(INPUT);
-        for _ in 0..39 {
-            output_sequence = look_and_say(&output_sequence);
-        }
-        assert_eq!(output_sequence.len(), 360154);
+        let sequence = get_input("sequence").unwrap();
+        assert_eq!(look_and_say_length(sequence.trim(), 40), 360154);
     }
 
     #[test]
     fn get_length_look_and_say50() {
-        let mut output_sequence = look_and_say(INPUT);
-        for _ in 0..49 {
-            output_sequence = look_and_say(&output_sequence);
-        }
-        assert_eq!(output_sequence.len(), 5103798);
+        let sequence = get_input("sequence").unwrap();
+        assert_eq!(look_and_say_length(sequence.trim(), 50), 5103798);
     }
 }
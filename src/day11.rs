@@ -1,6 +1,6 @@
-#[allow(dead_code)]
-const CURRENT_PASSWORD: &str = "cqjxjnds";
-
+use crate::compat::array_windows;
+use crate::parse::{Line, ParseError};
+use crate::solution::{Answer, Solution};
 const FORBIDDEN: [u8; 3] = [105, 108, 111];
 
 const ALPHABET: [u8; 23] = [
@@ -20,7 +20,7 @@ fn is_valid_password(password: &str) -> bool {
 
     // Check for straight while also checking no forbidden chars
     let mut has_straight = false;
-    for [a, b, c] in password.as_bytes().array_windows::<3>() {
+    for [a, b, c] in array_windows::<_, 3>(password.as_bytes()) {
         if FORBIDDEN.contains(c) {
             return false;
         }
@@ -35,7 +35,7 @@ fn is_valid_password(password: &str) -> bool {
     }
     // Check for two different pairs
     let mut first_pair: Option<(&u8, &u8)> = None;
-    for [a, b] in password.as_bytes().array_windows::<2>() {
+    for [a, b] in array_windows::<_, 2>(password.as_bytes()) {
         if first_pair.is_none() && a == b {
             first_pair = Some((a, b))
         } else if first_pair.is_some() && (a == b) && (first_pair.unwrap() != (a, b)) {
@@ -71,13 +71,48 @@ fn next_password(current_password: &mut str) {
     }
 }
 
+fn next_valid_password(current_password: &str) -> String {
+    let mut password = String::from(current_password);
+    while {
+        next_password(&mut password);
+        !is_valid_password(&password)
+    } {}
+    password
+}
+
+pub struct Day11 {
+    current_password: String,
+}
+
+impl Solution for Day11 {
+    /// Passwords must be exactly eight lowercase letters.
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let line = Line::single(input.trim());
+        if line.text.len() != 8 || !line.text.bytes().all(|c| c.is_ascii_lowercase()) {
+            return Err(line.error(line.text, "expected eight lowercase letters"));
+        }
+        Ok(Self {
+            current_password: line.text.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        next_valid_password(&self.current_password).into()
+    }
+
+    fn part2(&self) -> Answer {
+        next_valid_password(&next_valid_password(&self.current_password)).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn get_next_valid_password1() {
-        let mut password = String::from(CURRENT_PASSWORD);
+        let mut password = get_input("password").unwrap().trim().to_string();
         while !is_valid_password(&password) {
             next_password(&mut password);
         }
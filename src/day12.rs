@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
 use serde_json::{Map, Value};
 
 /// Just sum every number in the input, no deserialization required.
@@ -71,6 +73,44 @@ fn calculate_sum_ignore_red_array(numbers_array: &Vec<Value>) -> i64 {
     sum
 }
 
+pub struct Day12 {
+    numbers_json: String,
+    numbers: Value,
+}
+
+impl Solution for Day12 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let numbers = serde_json::from_str(input).map_err(|e| {
+            // serde_json reports where it gave up, point at the character there
+            let line = input.lines().nth(e.line().saturating_sub(1)).unwrap_or("");
+            let column = e.column().saturating_sub(1);
+            let token = line
+                .get(column..)
+                .and_then(|rest| rest.chars().next().map(|c| &rest[..c.len_utf8()]))
+                .unwrap_or("");
+            ParseError::new(e.line(), line, token, "invalid JSON")
+        })?;
+        Ok(Self {
+            numbers_json: input.to_string(),
+            numbers,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        calculate_sum(&self.numbers_json).into()
+    }
+
+    fn part2(&self) -> Answer {
+        match &self.numbers {
+            Value::Object(obj) => calculate_sum_ignore_red_object(obj),
+            Value::Array(array) => calculate_sum_ignore_red_array(array),
+            Value::Number(number) => number.as_i64().unwrap(),
+            _ => 0,
+        }
+        .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -92,4 +132,10 @@ mod solution {
         let numbers_obj = json.as_object().unwrap();
         assert_eq!(super::calculate_sum_ignore_red_object(numbers_obj), 68466);
     }
+
+    #[test]
+    fn parse_invalid_json() {
+        let e = Day12::parse("{\"a\":[1,2,\n3}").err().unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 2, "}"));
+    }
 }
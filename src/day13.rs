@@ -1,4 +1,7 @@
 /// This is the travelling salesman problem.
+use crate::compat::array_windows;
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
 
@@ -12,7 +15,7 @@ fn naive_tsp(happiness_matrix: &[Vec<i32>]) -> i32 {
         let mut happiness = 0;
 
         // Add happiness between guests
-        for [guest1, guest2] in seating.array_windows::<2>() {
+        for [guest1, guest2] in array_windows::<_, 2>(&seating) {
             happiness += happiness_matrix[*guest1][*guest2];
         }
         // Add happiness between last and first guest
@@ -25,19 +28,32 @@ fn naive_tsp(happiness_matrix: &[Vec<i32>]) -> i32 {
 }
 
 #[allow(dead_code)]
-fn build_happiness_matrix(happiness_rules: &str) -> Vec<Vec<i32>> {
+fn build_happiness_matrix(happiness_rules: &str) -> Result<Vec<Vec<i32>>, ParseError> {
     let mut happiness_matrix: Vec<Vec<i32>> = Vec::new();
     // Asign each guest an index starting with 0
     let mut index = 0;
     let mut guests_index: FxHashMap<&str, usize> = FxHashMap::default();
 
-    for line in happiness_rules.lines() {
-        let line: Vec<&str> = line.split(" ").collect();
-        let name1 = line[0];
-        let name2 = &line[10][..line[10].len() - 1]; // Remove period "."
-        let signed = line[2] == "lose";
+    for line in lines(happiness_rules) {
+        // Line example: "Alice would gain 2 happiness units by sitting next to Bob."
+        let tokens: Vec<&str> = line.text.split(' ').collect();
+        if tokens.len() != 11 {
+            return Err(line.error(
+                line.text,
+                "expected `<name> would <gain|lose> <n> happiness units by sitting next to <name>.`",
+            ));
+        }
+        let name1 = tokens[0];
+        let name2 = tokens[10]
+            .strip_suffix('.') // Remove period "."
+            .ok_or_else(|| line.error(tokens[10], "expected a name ending in `.`"))?;
+        let signed = match tokens[2] {
+            "gain" => false,
+            "lose" => true,
+            token => return Err(line.error(token, "expected `gain` or `lose`")),
+        };
         let happiness_change = {
-            let happiness_change = line[3].parse::<i32>().unwrap();
+            let happiness_change: i32 = line.parse(tokens[3], "a happiness change")?;
             if signed {
                 -happiness_change
             } else {
@@ -68,7 +84,43 @@ fn build_happiness_matrix(happiness_rules: &str) -> Vec<Vec<i32>> {
         happiness_matrix[*name1_index][*name2_index] += happiness_change;
         happiness_matrix[*name2_index][*name1_index] += happiness_change;
     }
-    happiness_matrix
+    if happiness_matrix.is_empty() {
+        let last_line = happiness_rules.lines().count();
+        return Err(ParseError::new(
+            last_line + 1,
+            "",
+            "",
+            "expected at least one guest",
+        ));
+    }
+    Ok(happiness_matrix)
+}
+
+pub struct Day13 {
+    happiness_matrix: Vec<Vec<i32>>,
+}
+
+impl Solution for Day13 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            happiness_matrix: build_happiness_matrix(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        naive_tsp(&self.happiness_matrix).into()
+    }
+
+    /// Seat myself as a guest with 0 happiness towards everyone.
+    fn part2(&self) -> Answer {
+        let mut happiness_matrix = self.happiness_matrix.clone();
+        let n = happiness_matrix.len();
+        happiness_matrix.push(vec![0; n + 1]);
+        for row in happiness_matrix.iter_mut().take(n) {
+            row.push(0);
+        }
+        naive_tsp(&happiness_matrix).into()
+    }
 }
 
 #[cfg(test)]
@@ -79,14 +131,14 @@ mod solution {
     #[test]
     fn get_highest_happiness() {
         let happiness = get_input("happiness").unwrap();
-        let happiness_matrix = build_happiness_matrix(&happiness);
+        let happiness_matrix = build_happiness_matrix(&happiness).unwrap();
         assert_eq!(naive_tsp(&happiness_matrix), 733);
     }
 
     #[test]
     fn get_highest_happiness_including_me() {
         let happiness = get_input("happiness").unwrap();
-        let mut happiness_matrix = build_happiness_matrix(&happiness);
+        let mut happiness_matrix = build_happiness_matrix(&happiness).unwrap();
         let n = happiness_matrix.len();
         happiness_matrix.push(vec![0; n + 1]);
         for row in happiness_matrix.iter_mut().take(n) {
@@ -105,4 +157,24 @@ mod solution {
         ];
         assert_eq!(naive_tsp(&happiness_matrix), 330);
     }
+
+    #[test]
+    fn build_happiness_matrix_invalid() {
+        let e = build_happiness_matrix("Alice would win 2 happiness units by sitting next to Bob.")
+            .unwrap_err();
+        assert_eq!((e.column, e.token.as_str()), (13, "win"));
+
+        let e = build_happiness_matrix("Alice would gain 2 happiness units by sitting next to Bob")
+            .unwrap_err();
+        assert_eq!((e.column, e.token.as_str()), (55, "Bob"));
+    }
+
+    #[test]
+    fn build_happiness_matrix_no_guests() {
+        let e = build_happiness_matrix("").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: expected at least one guest"
+        );
+    }
 }
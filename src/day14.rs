@@ -1,16 +1,21 @@
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashMap;
+use std::num::NonZeroU32;
 
 const TIME_LIMIT: u32 = 2503;
 
 #[allow(dead_code)]
+#[derive(Clone)]
 enum State {
     Sprinting,
     Resting,
 }
 
 #[allow(dead_code)]
-struct Reindeer<'a> {
-    _name: &'a str,
+#[derive(Clone)]
+struct Reindeer {
+    name: String,
     sprint_speed: u32,
     sprint_time: u32,
     rest_time: u32,
@@ -22,15 +27,15 @@ struct Reindeer<'a> {
 }
 
 #[allow(dead_code)]
-struct ReindeerRace<'a> {
-    reindeers: Vec<Reindeer<'a>>,
+struct ReindeerRace {
+    reindeers: Vec<Reindeer>,
 }
 
 #[allow(dead_code)]
-impl<'a> Reindeer<'a> {
-    fn new(name: &'a str, sprint_speed: u32, sprint_time: u32, rest_time: u32) -> Self {
+impl Reindeer {
+    fn new(name: String, sprint_speed: u32, sprint_time: u32, rest_time: u32) -> Self {
         Self {
-            _name: name,
+            name,
             sprint_speed,
             sprint_time,
             rest_time,
@@ -65,23 +70,47 @@ impl<'a> Reindeer<'a> {
     }
 }
 
+/// Parse a line of the form
+/// "Vixen can fly 8 km/s for 8 seconds, but then must rest for 53 seconds.". Both times have to
+/// be positive.
+fn parse_reindeer(line: Line<'_>) -> Result<Reindeer, ParseError> {
+    let tokens: Vec<&str> = line.text.split(' ').collect();
+    if tokens.len() != 15 {
+        return Err(line.error(
+            line.text,
+            "expected `<name> can fly <n> km/s for <n> seconds, but then must rest for <n> seconds.`",
+        ));
+    }
+    let sprint_time: NonZeroU32 = line.parse(tokens[6], "a positive sprint time")?;
+    let rest_time: NonZeroU32 = line.parse(tokens[13], "a positive rest time")?;
+    Ok(Reindeer::new(
+        tokens[0].to_string(),
+        line.parse(tokens[3], "a sprint speed")?,
+        sprint_time.get(),
+        rest_time.get(),
+    ))
+}
+
+fn parse_reindeers(reindeers: &str) -> Result<Vec<Reindeer>, ParseError> {
+    let parsed = lines(reindeers)
+        .map(parse_reindeer)
+        .collect::<Result<Vec<_>, _>>()?;
+    if parsed.is_empty() {
+        let last_line = reindeers.lines().count();
+        return Err(ParseError::new(
+            last_line + 1,
+            "",
+            "",
+            "expected at least one reindeer",
+        ));
+    }
+    Ok(parsed)
+}
+
 #[allow(dead_code)]
-impl<'a> ReindeerRace<'a> {
-    fn new(reindeers: &'a str) -> Self {
-        let mut reindeer_race = Self {
-            reindeers: Vec::new(),
-        };
-        for line in reindeers.lines() {
-            let line: Vec<&str> = line.split(" ").collect();
-            let name = line[0];
-            let sprint_speed: u32 = line[3].parse().unwrap();
-            let sprint_time: u32 = line[6].parse().unwrap();
-            let rest_time: u32 = line[13].parse().unwrap();
-            reindeer_race
-                .reindeers
-                .push(Reindeer::new(name, sprint_speed, sprint_time, rest_time));
-        }
-        reindeer_race
+impl ReindeerRace {
+    fn new(reindeers: Vec<Reindeer>) -> Self {
+        Self { reindeers }
     }
 
     fn step_second(&mut self) {
@@ -91,9 +120,9 @@ impl<'a> ReindeerRace<'a> {
         let furthest_distance_travelled = self
             .reindeers
             .iter()
-            .max_by_key(|r| r.distance_travelled)
-            .unwrap()
-            .distance_travelled;
+            .map(|r| r.distance_travelled)
+            .max()
+            .unwrap_or_default();
         for reindeer in self
             .reindeers
             .iter_mut()
@@ -105,15 +134,12 @@ impl<'a> ReindeerRace<'a> {
 }
 
 #[allow(dead_code)]
-fn find_fastest_reindeer(reindeers: &str) -> (&str, u32) {
+fn find_fastest_reindeer(reindeers: &[Reindeer]) -> Option<(&str, u32)> {
     let mut reindeer: FxHashMap<&str, u32> = FxHashMap::default();
 
-    for line in reindeers.lines() {
-        let line: Vec<&str> = line.split(" ").collect();
-        let name = line[0];
-        let sprint_speed: u32 = line[3].parse().unwrap();
-        let sprint_time: u32 = line[6].parse().unwrap();
-        let rest_time: u32 = line[13].parse().unwrap();
+    for r in reindeers {
+        let (name, sprint_speed, sprint_time, rest_time) =
+            (r.name.as_str(), r.sprint_speed, r.sprint_time, r.rest_time);
 
         let sprint_distance = sprint_time * sprint_speed;
         let sprint_rest_time = sprint_time + rest_time;
@@ -126,7 +152,38 @@ fn find_fastest_reindeer(reindeers: &str) -> (&str, u32) {
             reindeer.insert(name, (sprints + 1) * sprint_distance);
         }
     }
-    reindeer.into_iter().max_by_key(|v| v.1).unwrap()
+    reindeer.into_iter().max_by_key(|v| v.1)
+}
+
+pub struct Day14 {
+    reindeers: Vec<Reindeer>,
+}
+
+impl Solution for Day14 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            reindeers: parse_reindeers(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        find_fastest_reindeer(&self.reindeers)
+            .map(|(_, distance)| distance)
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut reindeer_race = ReindeerRace::new(self.reindeers.clone());
+        for _ in 0..TIME_LIMIT {
+            reindeer_race.step_second();
+        }
+        reindeer_race
+            .reindeers
+            .iter()
+            .map(|r| r.points)
+            .max()
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -136,15 +193,15 @@ mod solution {
 
     #[test]
     fn find_fastest_reindeer() {
-        let reindeers = get_input("reindeers").unwrap();
-        let fastest_reindeer = super::find_fastest_reindeer(&reindeers);
+        let reindeers = parse_reindeers(&get_input("reindeers").unwrap()).unwrap();
+        let fastest_reindeer = super::find_fastest_reindeer(&reindeers).unwrap();
         assert_eq!(fastest_reindeer.1, 2655);
     }
 
     #[test]
     fn find_fastest_reindeer_scored() {
-        let reindeers = get_input("reindeers").unwrap();
-        let mut reindeer_race = ReindeerRace::new(&reindeers);
+        let reindeers = parse_reindeers(&get_input("reindeers").unwrap()).unwrap();
+        let mut reindeer_race = ReindeerRace::new(reindeers);
         for _ in 0..TIME_LIMIT {
             reindeer_race.step_second();
         }
@@ -156,4 +213,21 @@ mod solution {
             .unwrap();
         assert_eq!(fastest_reindeer.points, 1059);
     }
+
+    #[test]
+    fn parse_reindeers_invalid() {
+        let e = parse_reindeers("").err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: expected at least one reindeer"
+        );
+
+        let e = parse_reindeers(
+            "Vixen can fly 8 km/s for 8 seconds, but then must rest for 53 seconds.\n\
+             Comet can fly 13 km/s for 0 seconds, but then must rest for 0 seconds.",
+        )
+        .err()
+        .unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 27, "0"));
+    }
 }
@@ -1,7 +1,142 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
+use ndarray::Array2;
+
+/// Build the properties matrix (a row per property, a column per ingredient) and the calories
+/// row vector from lines of the form
+/// "Sugar: capacity 0, durability 0, flavor -2, texture 2, calories 1".
+fn build_properties_matrix(
+    cookie_properties: &str,
+) -> Result<(Array2<i64>, Array2<i64>), ParseError> {
+    const PROPERTIES: [&str; 5] = ["capacity", "durability", "flavor", "texture", "calories"];
+    let mut ingredients: Vec<[i64; 5]> = Vec::new();
+    for line in lines(cookie_properties) {
+        let (_, properties) = line
+            .text
+            .split_once(": ")
+            .ok_or_else(|| line.error("", "expected `<ingredient>: <properties>`"))?;
+        let mut properties = properties.split(", ");
+        let mut ingredient = [0; 5];
+        for (i, name) in PROPERTIES.iter().enumerate() {
+            let mut property = line.expect(properties.next(), name)?.split(' ');
+            line.expect_literal(property.next(), name)?;
+            ingredient[i] = line.parse_next(&mut property, "a number")?;
+            line.expect_end(&mut property)?;
+        }
+        line.expect_end(&mut properties)?;
+        ingredients.push(ingredient);
+    }
+    if ingredients.is_empty() {
+        let last_line = cookie_properties.lines().count();
+        return Err(ParseError::new(
+            last_line + 1,
+            "",
+            "",
+            "expected at least one ingredient",
+        ));
+    }
+    let n = ingredients.len();
+    let properties = Array2::from_shape_fn((4, n), |(i, j)| ingredients[j][i]);
+    let calories = Array2::from_shape_fn((1, n), |(_, j)| ingredients[j][4]);
+    Ok((properties, calories))
+}
+
+/// Try every way of dividing the remaining teaspoons between the remaining ingredients.
+fn highest_score(
+    properties: &Array2<i64>,
+    calories: Option<(&Array2<i64>, i64)>,
+    teaspoons: &mut Vec<i64>,
+    remaining_teaspoons: i64,
+) -> i64 {
+    if teaspoons.len() + 1 == properties.ncols() {
+        // The last ingredient takes whatever is left
+        teaspoons.push(remaining_teaspoons);
+        let x = Array2::from_shape_fn((teaspoons.len(), 1), |(i, _)| teaspoons[i]);
+        teaspoons.pop();
+        if let Some((calories, target)) = calories {
+            if calories.dot(&x)[[0, 0]] != target {
+                return 0;
+            }
+        }
+        return properties
+            .dot(&x)
+            .map(|property_score| {
+                if *property_score < 0 {
+                    0
+                } else {
+                    *property_score
+                }
+            })
+            .product();
+    }
+    let mut highest = 0;
+    for ingredient_teaspoons in 0..=remaining_teaspoons {
+        teaspoons.push(ingredient_teaspoons);
+        highest = std::cmp::max(
+            highest,
+            highest_score(
+                properties,
+                calories,
+                teaspoons,
+                remaining_teaspoons - ingredient_teaspoons,
+            ),
+        );
+        teaspoons.pop();
+    }
+    highest
+}
+
+pub struct Day15 {
+    properties: Array2<i64>,
+    calories: Array2<i64>,
+}
+
+impl Solution for Day15 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (properties, calories) = build_properties_matrix(input)?;
+        Ok(Self {
+            properties,
+            calories,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        highest_score(&self.properties, None, &mut Vec::new(), 100).into()
+    }
+
+    fn part2(&self) -> Answer {
+        highest_score(
+            &self.properties,
+            Some((&self.calories, 500)),
+            &mut Vec::new(),
+            100,
+        )
+        .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
+    use super::*;
+    use crate::input::get_input::get_input;
     use ndarray::arr2;
 
+    #[test]
+    fn optimize_cookie_from_input() {
+        let day = Day15::parse(&get_input("cookie_properties").unwrap()).unwrap();
+        assert_eq!(day.part1(), Answer::Int(18965440));
+        assert_eq!(day.part2(), Answer::Int(15862900));
+    }
+
+    #[test]
+    fn build_properties_matrix_no_ingredients() {
+        let e = build_properties_matrix("").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: expected at least one ingredient"
+        );
+    }
+
     #[test]
     fn optimize_cookie_simple() {
         let properties = arr2(&[[-1i64, 2], [-2, 3], [6, -2], [3, -1]]);
@@ -1,6 +1,41 @@
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashMap;
+
+/// Sue number and her known attributes.
+type Sue = (i32, Vec<(String, i32)>);
+
+/// Parse a line of the form "Sue 1: goldfish: 9, cars: 0, samoyeds: 9" to
+/// (sue number, attributes).
+fn parse_sue(line: Line<'_>) -> Result<Sue, ParseError> {
+    let (sue_num, attributes) = line
+        .text
+        .split_once(": ")
+        .ok_or_else(|| line.error("", "expected `Sue <n>: <attributes>`"))?;
+    let mut sue_num_split = sue_num.split(' ');
+    line.expect_literal(sue_num_split.next(), "Sue")?;
+    let sue_num = line.parse_next(&mut sue_num_split, "a number")?;
+    line.expect_end(&mut sue_num_split)?;
+
+    let mut parsed_attributes = Vec::new();
+    for attribute in attributes.split(", ") {
+        let (attribute_name, attribute_num) = attribute
+            .split_once(": ")
+            .ok_or_else(|| line.error(attribute, "expected `<attribute>: <n>`"))?;
+        parsed_attributes.push((
+            attribute_name.to_string(),
+            line.parse(attribute_num, "a number")?,
+        ));
+    }
+    Ok((sue_num, parsed_attributes))
+}
+
+fn parse_sues(sues: &str) -> Result<Vec<Sue>, ParseError> {
+    lines(sues).map(parse_sue).collect()
+}
+
 #[allow(dead_code)]
-fn find_sue<F>(sues: &str, filter: F) -> Option<i32>
+fn find_sue<F>(sues: &[Sue], filter: F) -> Option<i32>
 where
     F: Fn(&str, i32, i32) -> bool,
 {
@@ -16,33 +51,59 @@ where
     ticker_tape.insert("cars", 2);
     ticker_tape.insert("perfumes", 1);
 
-    for line in sues.lines() {
-        let (sue_num, attributes) = line.split_once(": ").unwrap();
-
-        let attributes_split = attributes.split(", ");
+    for (sue_num, attributes) in sues {
         let mut all_match = true;
-        for attribute in attributes_split {
-            let mut attribute_split = attribute.split(": ");
-            let attribute_name = attribute_split.next().unwrap();
-            let attribute_num = attribute_split.next().unwrap().parse::<i32>().unwrap();
-            if let Some(ticker_attribute_num) = ticker_tape.get(attribute_name) {
-                all_match &= filter(attribute_name, attribute_num, *ticker_attribute_num);
+        for (attribute_name, attribute_num) in attributes {
+            if let Some(ticker_attribute_num) = ticker_tape.get(attribute_name.as_str()) {
+                all_match &= filter(attribute_name, *attribute_num, *ticker_attribute_num);
             }
         }
         if all_match {
-            return Some(sue_num.split(" ").nth(1).unwrap().parse::<i32>().unwrap());
+            return Some(*sue_num);
         }
     }
     None
 }
 
+pub struct Day16 {
+    sues: Vec<Sue>,
+}
+
+impl Solution for Day16 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            sues: parse_sues(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        find_sue(&self.sues, |_, attribute_num, ticker_attribute_num| {
+            ticker_attribute_num == attribute_num
+        })
+        .into()
+    }
+
+    fn part2(&self) -> Answer {
+        find_sue(
+            &self.sues,
+            |attribute_name, attribute_num, ticker_attribute_num| match attribute_name {
+                "cats" | "trees" => attribute_num > ticker_attribute_num,
+                "pomeranians" | "goldfish" => attribute_num < ticker_attribute_num,
+                _ => attribute_num == ticker_attribute_num,
+            },
+        )
+        .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
+    use super::parse_sues;
     use crate::input::get_input::get_input;
 
     #[test]
     fn find_sue() {
-        let sues = get_input("sues").unwrap();
+        let sues = parse_sues(&get_input("sues").unwrap()).unwrap();
         let sue = super::find_sue(&sues, |_, attribute_num, ticker_attribute_num| {
             ticker_attribute_num == attribute_num
         })
@@ -52,7 +113,7 @@ mod solution {
 
     #[test]
     fn find_sue_actual() {
-        let sues = get_input("sues").unwrap();
+        let sues = parse_sues(&get_input("sues").unwrap()).unwrap();
         let filter =
             |attribute_name: &str, attribute_num, ticker_attribute_num| match attribute_name {
                 "cats" | "trees" => attribute_num > ticker_attribute_num,
@@ -62,4 +123,11 @@ mod solution {
         let sue = super::find_sue(&sues, filter).unwrap();
         assert_eq!(sue, 241);
     }
+
+    #[test]
+    fn parse_sue_invalid() {
+        let sues = "Sue 1: goldfish: 9, cars: 0\nSue 2: perfumes 5, trees: 8";
+        let e = parse_sues(sues).unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 8, "perfumes 5"));
+    }
 }
@@ -1,3 +1,5 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use std::cmp::Ordering;
 
 #[allow(dead_code)]
@@ -39,52 +41,65 @@ fn count_container_combinations(
     *depth -= 1;
 }
 
+/// Returns (number of combinations, minimum depth, number of combinations with minimum depth).
+fn count_combinations(containers: &[i32], eggnog: i32, min_depth_known: i32) -> (i32, i32, i32) {
+    let mut count = 0;
+    let mut depth = 1;
+    let mut min_depth = i32::MAX;
+    let mut min_depth_count = 0;
+    count_container_combinations(
+        containers,
+        eggnog,
+        &mut count,
+        &mut depth,
+        min_depth_known,
+        &mut min_depth,
+        &mut min_depth_count,
+    );
+    (count, min_depth, min_depth_count)
+}
+
+pub struct Day17 {
+    containers: Vec<i32>,
+}
+
+impl Solution for Day17 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            containers: lines(input)
+                .map(|line| line.parse(line.text, "a container size"))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        count_combinations(&self.containers, 150, 0).0.into()
+    }
+
+    /// The minimum depth has to be known up front, so find it first.
+    fn part2(&self) -> Answer {
+        let (_, min_depth, _) = count_combinations(&self.containers, 150, 0);
+        count_combinations(&self.containers, 150, min_depth)
+            .2
+            .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
+    use super::count_combinations;
     use crate::input::get_input::get_input;
 
     #[test]
     fn count_container_combinations_simple() {
         let containers: Vec<i32> = vec![20, 15, 10, 5, 5];
-        let mut count = 0;
-        let mut depth = 1;
-        let min_depth_known = 2;
-        let mut min_depth = i32::MAX;
-        let mut min_depth_count = 0;
-        super::count_container_combinations(
-            &containers,
-            25,
-            &mut count,
-            &mut depth,
-            min_depth_known,
-            &mut min_depth,
-            &mut min_depth_count,
-        );
-        assert_eq!(count, 4);
-        assert_eq!(min_depth, min_depth_known);
-        assert_eq!(min_depth_count, 3);
+        assert_eq!(count_combinations(&containers, 25, 2), (4, 2, 3));
     }
 
     #[test]
     fn count_container_combinations() {
         let containers = get_input("containers").unwrap();
         let containers: Vec<i32> = containers.lines().map(|v| v.parse().unwrap()).collect();
-        let mut count = 0;
-        let mut depth = 1;
-        let min_depth_known = 4;
-        let mut min_depth = i32::MAX;
-        let mut min_depth_count = 0;
-        super::count_container_combinations(
-            &containers,
-            150,
-            &mut count,
-            &mut depth,
-            min_depth_known,
-            &mut min_depth,
-            &mut min_depth_count,
-        );
-        assert_eq!(count, 1304);
-        assert_eq!(min_depth, min_depth_known);
-        assert_eq!(min_depth_count, 18);
+        assert_eq!(count_combinations(&containers, 150, 4), (1304, 4, 18));
     }
 }
@@ -1,26 +1,49 @@
 //! This is essentially game of life.
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use std::str::FromStr;
 
 #[allow(dead_code)]
+#[derive(Clone)]
 struct LightGrid {
     grid: [[bool; 10000]; 2],
 }
 
 impl FromStr for LightGrid {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(light_grid_str: &str) -> Result<Self, Self::Err> {
         let mut light_grid = [[false; 10000]; 2];
-        let mut i = 0;
-        for b in light_grid_str.as_bytes() {
-            match b {
-                b'.' => i += 1,
-                b'#' => {
-                    light_grid[0][i] = true;
-                    i += 1;
+        let mut rows = 0;
+        for line in lines(light_grid_str) {
+            if line.number > Self::ROWS {
+                return Err(line.error(line.text, format!("expected {} rows", Self::ROWS)));
+            }
+            let mut lights = line.text.char_indices();
+            for j in 0..Self::ROWS {
+                match lights.next() {
+                    Some((_, '.')) => (),
+                    Some((_, '#')) => light_grid[0][(line.number - 1) * Self::ROWS + j] = true,
+                    Some((k, c)) => {
+                        return Err(
+                            line.error(&line.text[k..k + c.len_utf8()], "expected `.` or `#`")
+                        )
+                    }
+                    None => return Err(line.error("", format!("expected {} lights", Self::ROWS))),
                 }
-                _ => (),
             }
+            if let Some((k, _)) = lights.next() {
+                return Err(line.error(&line.text[k..], "unexpected trailing input"));
+            }
+            rows = line.number;
+        }
+        if rows < Self::ROWS {
+            return Err(ParseError::new(
+                rows + 1,
+                "",
+                "",
+                format!("expected {} rows", Self::ROWS),
+            ));
         }
         Ok(Self { grid: light_grid })
     }
@@ -124,6 +147,32 @@ impl LightGrid {
     }
 }
 
+pub struct Day18 {
+    light_grid: LightGrid,
+}
+
+impl Solution for Day18 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            light_grid: input.parse()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.light_grid.clone().step_100().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut light_grid = self.light_grid.clone();
+        for grid in 0..2 {
+            for (i, j) in LightGrid::CORNERS {
+                light_grid.set(grid, i, j, true);
+            }
+        }
+        light_grid.step_100_corners_always_on().into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -148,4 +197,15 @@ mod solution {
         }
         assert_eq!(light_grid.step_100_corners_always_on(), 1006);
     }
+
+    #[test]
+    fn parse_light_grid_invalid() {
+        let mut light_grid = get_input("light_grid").unwrap();
+        light_grid.replace_range(105..106, "x");
+        let e = light_grid.parse::<LightGrid>().err().unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 5, "x"));
+
+        let e = "#.#".parse::<LightGrid>().err().unwrap();
+        assert_eq!(e.to_string(), "line 1, column 4: expected 100 lights");
+    }
 }
@@ -1,3 +1,5 @@
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 struct Elements<'a> {
@@ -26,48 +28,58 @@ impl<'a> Iterator for Elements<'a> {
 }
 
 trait ElementsExt {
-    fn elements(&self) -> Elements;
+    fn elements(&self) -> Elements<'_>;
 }
 
 impl ElementsExt for str {
-    fn elements(&self) -> Elements {
+    fn elements(&self) -> Elements<'_> {
         Elements { s: self, index: 0 }
     }
 }
 
+/// Split a line of the form "Al => ThF".
+fn parse_replacement(line: Line<'_>) -> Result<(&str, &str), ParseError> {
+    line.text
+        .split_once(" => ")
+        .ok_or_else(|| line.error("", "expected `<molecule> => <molecule>`"))
+}
+
 #[allow(dead_code)]
-fn get_replacements(replacements_str: &str) -> FxHashMap<&str, Vec<&str>> {
+fn get_replacements(replacements_str: &str) -> Result<FxHashMap<String, Vec<String>>, ParseError> {
     let mut replacements = FxHashMap::default();
 
-    for line in replacements_str.lines() {
-        let mut line_split = line.split(" => ");
-        let molecule = line_split.next().unwrap();
-        let replaced_with = line_split.next().unwrap();
+    for line in lines(replacements_str) {
+        let (molecule, replaced_with) = parse_replacement(line)?;
         replacements
-            .entry(molecule)
-            .and_modify(|entry: &mut Vec<&str>| entry.push(replaced_with))
-            .or_insert(vec![replaced_with]);
+            .entry(molecule.to_string())
+            .or_insert_with(Vec::new)
+            .push(replaced_with.to_string());
     }
-    replacements
+    Ok(replacements)
 }
 
 #[allow(dead_code)]
-fn get_reverse_replacements(replacements_str: &str) -> FxHashMap<&str, &str> {
+fn get_reverse_replacements(
+    replacements_str: &str,
+) -> Result<FxHashMap<String, String>, ParseError> {
     let mut replacements = FxHashMap::default();
 
-    for line in replacements_str.lines() {
-        let mut line_split = line.split(" => ");
-        let molecule = line_split.next().unwrap();
-        let replaced_with = line_split.next().unwrap();
-        assert!(replacements.insert(replaced_with, molecule).is_none());
+    for line in lines(replacements_str) {
+        let (molecule, replaced_with) = parse_replacement(line)?;
+        if replacements
+            .insert(replaced_with.to_string(), molecule.to_string())
+            .is_some()
+        {
+            return Err(line.error(replaced_with, "duplicate replacement"));
+        }
     }
-    replacements
+    Ok(replacements)
 }
 
 #[allow(dead_code)]
 fn calibrate_machine(
     medicine: &str,
-    replacements: FxHashMap<&str, Vec<&str>>,
+    replacements: &FxHashMap<String, Vec<String>>,
 ) -> FxHashSet<String> {
     let mut calibration: FxHashSet<String> = FxHashSet::default();
     let mut i = 0;
@@ -88,6 +100,58 @@ fn calibrate_machine(
     calibration
 }
 
+pub struct Day19 {
+    replacements: FxHashMap<String, Vec<String>>,
+    reverse_replacements: FxHashMap<String, String>,
+    medicine: String,
+}
+
+impl Solution for Day19 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let missing_medicine = || {
+            let last_line = input.lines().count();
+            ParseError::new(last_line + 1, "", "", "expected the medicine molecule")
+        };
+        let (replacements, medicine) = input.split_once("\n\n").ok_or_else(missing_medicine)?;
+        let medicine = medicine.trim_end();
+        if medicine.is_empty() {
+            return Err(missing_medicine());
+        }
+        Ok(Self {
+            replacements: get_replacements(replacements)?,
+            reverse_replacements: get_reverse_replacements(replacements)?,
+            medicine: medicine.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        calibrate_machine(&self.medicine, &self.replacements)
+            .len()
+            .into()
+    }
+
+    /// Greedily undo replacements until only "e" is left. This works by luck, and gives up when
+    /// no replacement can be undone.
+    fn part2(&self) -> Answer {
+        let mut medicine = self.medicine.clone();
+
+        let mut count: usize = 0;
+        while medicine != "e" {
+            let previous_count = count;
+            for (k, v) in &self.reverse_replacements {
+                if let Some(index) = medicine.rfind(k.as_str()) {
+                    medicine.replace_range(index..index + k.len(), v);
+                    count += 1;
+                }
+            }
+            if count == previous_count {
+                return Answer::None;
+            }
+        }
+        count.into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -97,14 +161,14 @@ mod solution {
     fn calibrate_machine() {
         let replacements = get_input("replacements").unwrap();
         let mut replacements_split = replacements.split("\n\n");
-        let replacements_map = get_replacements(replacements_split.next().unwrap());
+        let replacements_map = get_replacements(replacements_split.next().unwrap()).unwrap();
         let calibration = super::calibrate_machine(
             replacements_split
                 .next()
                 .unwrap()
                 .strip_suffix("\n")
                 .unwrap(),
-            replacements_map,
+            &replacements_map,
         );
         assert_eq!(calibration.len(), 535);
     }
@@ -113,7 +177,8 @@ mod solution {
     fn deconstruct_medicine() {
         let replacements = get_input("replacements").unwrap();
         let mut replacements_split = replacements.split("\n\n");
-        let reverse_replacements_map = get_reverse_replacements(replacements_split.next().unwrap());
+        let reverse_replacements_map =
+            get_reverse_replacements(replacements_split.next().unwrap()).unwrap();
         let mut medicine = replacements_split
             .next()
             .unwrap()
@@ -125,8 +190,8 @@ mod solution {
         let mut count = 0;
         while medicine != "e" {
             for k in reverse_replacements_map.keys() {
-                if medicine.contains(k) {
-                    let index = medicine.rfind(k).unwrap();
+                if medicine.contains(k.as_str()) {
+                    let index = medicine.rfind(k.as_str()).unwrap();
                     medicine.replace_range(
                         index..index + k.len(),
                         reverse_replacements_map.get(k).unwrap(),
@@ -137,4 +202,16 @@ mod solution {
         }
         assert_eq!(count, 212);
     }
+
+    #[test]
+    fn parse_empty_medicine() {
+        let e = Day19::parse("e => H\nH => HO\n\n").err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: expected the medicine molecule"
+        );
+
+        let day = Day19::parse("e => H\nH => HO\n\nOH\n").unwrap();
+        assert_eq!(day.part2(), Answer::None);
+    }
 }
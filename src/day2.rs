@@ -1,46 +1,391 @@
+use crate::parse::{parse_lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Present {
+    length: i64,
+    width: i64,
+    height: i64,
+}
+
+impl Present {
+    pub fn dimensions(&self) -> [i64; 3] {
+        [self.length, self.width, self.height]
+    }
+
+    /// Dimensions from shortest to longest.
+    pub fn sorted_dimensions(&self) -> [i64; 3] {
+        let mut dimensions = self.dimensions();
+        dimensions.sort_unstable();
+        dimensions
+    }
+
+    pub fn surface_area(&self) -> i64 {
+        2 * (self.length * self.width + self.width * self.height + self.height * self.length)
+    }
+
+    pub fn smallest_face_area(&self) -> i64 {
+        let [a, b, _] = self.sorted_dimensions();
+        a * b
+    }
+
+    pub fn smallest_face_perimeter(&self) -> i64 {
+        let [a, b, _] = self.sorted_dimensions();
+        2 * (a + b)
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.length * self.width * self.height
+    }
+
+    /// The paper and ribbon needed for the present, if they fit in an `i64`.
+    fn checked_paper_and_ribbon(&self) -> Option<(i64, i64)> {
+        let [a, b, c] = self.sorted_dimensions();
+        let smallest_face = a.checked_mul(b)?;
+        let faces = smallest_face
+            .checked_add(b.checked_mul(c)?)?
+            .checked_add(c.checked_mul(a)?)?;
+        let paper = faces.checked_mul(2)?.checked_add(smallest_face)?;
+        let ribbon = a
+            .checked_add(b)?
+            .checked_mul(2)?
+            .checked_add(smallest_face.checked_mul(c)?)?;
+        Some((paper, ribbon))
+    }
+}
+
+impl FromStr for Present {
+    type Err = ParseError;
+
+    /// Parse a present of the form "lxwxh".
+    fn from_str(present_str: &str) -> Result<Self, Self::Err> {
+        let line = Line::single(present_str);
+        let mut dims = present_str.split('x');
+        let mut next_dimension = || -> Result<i64, ParseError> {
+            let token = line.expect(dims.next(), "a dimension")?;
+            match line.parse(token, "a dimension")? {
+                dimension if dimension > 0 => Ok(dimension),
+                _ => Err(line.error(token, "expected a positive dimension")),
+            }
+        };
+        let present = Self {
+            length: next_dimension()?,
+            width: next_dimension()?,
+            height: next_dimension()?,
+        };
+        line.expect_end(&mut dims)?;
+        if present.checked_paper_and_ribbon().is_none() {
+            return Err(line.error(present_str, "present is too large"));
+        }
+        Ok(present)
+    }
+}
+
 #[allow(dead_code)]
 /// Decompose a string of the form "lxwxh" to [i64; 3].
-fn decompose_present_dimensions(present: &str) -> [i64; 3] {
-    let mut dims = present.split('x');
-    // We can make the assumption that there are always 3 numbers and they are valid ints.
-    [
-        dims.next().unwrap().parse().unwrap(),
-        dims.next().unwrap().parse().unwrap(),
-        dims.next().unwrap().parse().unwrap(),
-    ]
+fn decompose_present_dimensions(present: &str) -> Result<[i64; 3], ParseError> {
+    Ok(present.parse::<Present>()?.dimensions())
 }
 
-#[allow(dead_code)]
-fn calculate_required_wrapping_paper(presents: &str) -> i64 {
-    let mut total: i64 = 0;
-    for present in presents.lines() {
-        let [l, w, h] = decompose_present_dimensions(present);
-        let faces_area = [l * w, w * h, h * l];
-        total = total + 2 * faces_area.iter().sum::<i64>() + faces_area.iter().min().unwrap();
+/// How much of something (paper, ribbon, money...) the elves need for each present.
+pub trait WrappingPolicy {
+    fn required(&self, present: &Present) -> i64;
+
+    /// The requirement of all presents together, or `None` if it doesn't fit in an i64.
+    fn total(&self, presents: &[Present]) -> Option<i64> {
+        presents.iter().try_fold(0i64, |total, present| {
+            total.checked_add(self.required(present))
+        })
+    }
+}
+
+/// Any function of a present is a policy.
+impl<F: Fn(&Present) -> i64> WrappingPolicy for F {
+    fn required(&self, present: &Present) -> i64 {
+        self(present)
+    }
+}
+
+/// Paper covering every face, plus the area of the smallest face as slack.
+pub struct Paper;
+
+impl WrappingPolicy for Paper {
+    fn required(&self, present: &Present) -> i64 {
+        present.surface_area() + present.smallest_face_area()
+    }
+}
+
+/// Ribbon around the smallest perimeter, plus a bow as long as the present's volume.
+pub struct Ribbon;
+
+impl WrappingPolicy for Ribbon {
+    fn required(&self, present: &Present) -> i64 {
+        present.smallest_face_perimeter() + present.volume()
+    }
+}
+
+/// Ribbon around the smallest perimeter, with bows of a fixed length.
+pub struct FixedBowRibbon(pub i64);
+
+impl WrappingPolicy for FixedBowRibbon {
+    fn required(&self, present: &Present) -> i64 {
+        present.smallest_face_perimeter() + self.0
+    }
+}
+
+/// Another policy's requirement multiplied by a cost per unit.
+pub struct Cost<P: WrappingPolicy> {
+    pub policy: P,
+    pub per_unit: i64,
+}
+
+impl<P: WrappingPolicy> WrappingPolicy for Cost<P> {
+    fn required(&self, present: &Present) -> i64 {
+        self.policy.required(present) * self.per_unit
+    }
+}
+
+/// The sum of two policies, e.g. the cost of paper and ribbon.
+pub struct Both<A: WrappingPolicy, B: WrappingPolicy>(pub A, pub B);
+
+impl<A: WrappingPolicy, B: WrappingPolicy> WrappingPolicy for Both<A, B> {
+    fn required(&self, present: &Present) -> i64 {
+        self.0.required(present) + self.1.required(present)
     }
-    total
 }
 
 #[allow(dead_code)]
-fn calculate_required_ribbon(presents: &str) -> i64 {
-    let mut total = 0;
-    for present in presents.lines() {
-        let dims = decompose_present_dimensions(present);
-        let (longest_side, _) = dims
-            .iter()
-            .enumerate()
-            .max_by_key(|(_, &side_length)| side_length)
-            .unwrap();
-        // Add length of 2 shortest sides twice
-        for (side, length) in dims.iter().enumerate() {
-            if side == longest_side {
-                continue;
+fn calculate_required_wrapping_paper(presents: &[Present]) -> Option<i64> {
+    Paper.total(presents)
+}
+
+#[allow(dead_code)]
+fn calculate_required_ribbon(presents: &[Present]) -> Option<i64> {
+    Ribbon.total(presents)
+}
+
+/// The paper and ribbon needed for one line of an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderLine {
+    pub line: usize,
+    pub present: Present,
+    pub paper: i64,
+    pub ribbon: i64,
+}
+
+impl OrderLine {
+    pub const CSV_HEADER: &'static str = "line,length,width,height,paper,ribbon";
+
+    pub fn to_csv(self) -> String {
+        let [length, width, height] = self.present.dimensions();
+        format!(
+            "{},{},{},{},{},{}",
+            self.line, length, width, height, self.paper, self.ribbon
+        )
+    }
+
+    pub fn to_json(self) -> serde_json::Value {
+        json!({
+            "line": self.line,
+            "dimensions": self.present.dimensions(),
+            "paper": self.paper,
+            "ribbon": self.ribbon,
+        })
+    }
+}
+
+/// Reads an order one line at a time, so it never has to be in memory as a whole.
+pub struct OrderLines<R: BufRead> {
+    reader: R,
+    line: usize,
+    buffer: String,
+}
+
+impl<R: BufRead> OrderLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 0,
+            buffer: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for OrderLines<R> {
+    type Item = Result<OrderLine, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(e) => return Some(Err(e.into())),
+        }
+        self.line += 1;
+        let present = self
+            .buffer
+            .trim_end_matches(['\n', '\r'])
+            .parse::<Present>()
+            .map_err(|e| e.on_line(self.line));
+        Some(match present {
+            Ok(present) => Ok(OrderLine {
+                line: self.line,
+                present,
+                paper: Paper.required(&present),
+                ribbon: Ribbon.required(&present),
+            }),
+            Err(e) => Err(e.into()),
+        })
+    }
+}
+
+/// Totals, a histogram of present volumes and the largest consumers of an order, built up a line
+/// at a time.
+#[derive(Debug, Default)]
+pub struct OrderReport {
+    pub presents: usize,
+    pub paper: i64,
+    pub ribbon: i64,
+    /// Number of presents by volume, bucketed by powers of two: each key is the smallest volume
+    /// in its bucket.
+    pub volume_histogram: BTreeMap<i64, usize>,
+    /// How many of the largest consumers to keep.
+    pub top: usize,
+    /// The lines needing the most paper, most first.
+    pub top_paper: Vec<OrderLine>,
+    /// The lines needing the most ribbon, most first.
+    pub top_ribbon: Vec<OrderLine>,
+}
+
+impl OrderReport {
+    pub fn new(top: usize) -> Self {
+        Self {
+            top,
+            ..Self::default()
+        }
+    }
+
+    /// Add a line to the report, failing if the total paper or ribbon doesn't fit in an i64.
+    pub fn add(&mut self, order_line: &OrderLine) -> Result<(), ParseError> {
+        let too_large = |what: &str| {
+            ParseError::new(
+                order_line.line,
+                "",
+                "",
+                format!("total {} is too large", what),
+            )
+        };
+        self.paper = self
+            .paper
+            .checked_add(order_line.paper)
+            .ok_or_else(|| too_large("paper"))?;
+        self.ribbon = self
+            .ribbon
+            .checked_add(order_line.ribbon)
+            .ok_or_else(|| too_large("ribbon"))?;
+        self.presents += 1;
+        let volume = order_line.present.volume();
+        *self
+            .volume_histogram
+            .entry(1 << volume.ilog2())
+            .or_default() += 1;
+        Self::keep_top(&mut self.top_paper, self.top, order_line, |line| line.paper);
+        Self::keep_top(&mut self.top_ribbon, self.top, order_line, |line| {
+            line.ribbon
+        });
+        Ok(())
+    }
+
+    /// Insert the line if it's among the `top` largest, earlier lines winning ties.
+    fn keep_top(
+        largest: &mut Vec<OrderLine>,
+        top: usize,
+        order_line: &OrderLine,
+        amount: impl Fn(&OrderLine) -> i64,
+    ) {
+        let i = largest.partition_point(|line| amount(line) >= amount(order_line));
+        if i < top {
+            largest.insert(i, *order_line);
+            largest.truncate(top);
+        }
+    }
+
+    /// Aggregate a whole order.
+    pub fn from_reader(reader: impl BufRead, top: usize) -> Result<Self, Box<dyn Error>> {
+        Self::write_breakdown(reader, io::sink(), Format::Csv, top)
+    }
+
+    /// Aggregate an order while writing out the figures of every line.
+    pub fn write_breakdown(
+        reader: impl BufRead,
+        mut out: impl Write,
+        format: Format,
+        top: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut report = Self::new(top);
+        if format == Format::Csv {
+            writeln!(out, "{}", OrderLine::CSV_HEADER)?;
+        }
+        for order_line in OrderLines::new(reader) {
+            let order_line = order_line?;
+            match format {
+                Format::Csv => writeln!(out, "{}", order_line.to_csv())?,
+                Format::JsonLines => writeln!(out, "{}", order_line.to_json())?,
             }
-            total += 2 * *length;
+            report.add(&order_line)?;
         }
-        total += dims.into_iter().product::<i64>();
+        Ok(report)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let lines =
+            |lines: &[OrderLine]| -> Vec<_> { lines.iter().map(|line| line.to_json()).collect() };
+        json!({
+            "presents": self.presents,
+            "paper": self.paper,
+            "ribbon": self.ribbon,
+            "volume_histogram": self
+                .volume_histogram
+                .iter()
+                .map(|(volume, count)| json!({ "min_volume": volume, "presents": count }))
+                .collect::<Vec<_>>(),
+            "top_paper": lines(&self.top_paper),
+            "top_ribbon": lines(&self.top_ribbon),
+        })
+    }
+}
+
+/// Output format of the per-line breakdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+pub struct Day2 {
+    presents: Vec<Present>,
+}
+
+impl Solution for Day2 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            presents: parse_lines::<Present>(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        calculate_required_wrapping_paper(&self.presents).into()
+    }
+
+    fn part2(&self) -> Answer {
+        calculate_required_ribbon(&self.presents).into()
     }
-    total
 }
 
 #[cfg(test)]
@@ -51,19 +396,139 @@ mod solution {
     #[test]
     fn decompose_test() {
         let s: &str = "24x25x17";
-        let dims = decompose_present_dimensions(s);
+        let dims = decompose_present_dimensions(s).unwrap();
         assert_eq!(dims, [24, 25, 17]);
     }
 
+    #[test]
+    fn decompose_invalid() {
+        let e = parse_lines::<Present>("1x2x3\n24x2a5x17").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 4, "2a5"));
+
+        let e = decompose_present_dimensions("24x25").unwrap_err();
+        assert_eq!(e.to_string(), "line 1, column 6: expected a dimension");
+
+        let e = "24x0x17".parse::<Present>().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 4: expected a positive dimension, found `0`"
+        );
+    }
+
+    #[test]
+    fn wrapping_policies() {
+        let present: Present = "2x3x4".parse().unwrap();
+        assert_eq!(Paper.required(&present), 58);
+        assert_eq!(Ribbon.required(&present), 34);
+        assert_eq!(FixedBowRibbon(5).required(&present), 15);
+
+        let presents = [present, "1x1x10".parse().unwrap()];
+        assert_eq!(
+            Both(Paper, Ribbon).total(&presents),
+            Some(58 + 43 + 34 + 14)
+        );
+        let cost = Cost {
+            policy: Paper,
+            per_unit: 3,
+        };
+        assert_eq!(cost.total(&presents), Some(3 * (58 + 43)));
+        let volume = |present: &Present| present.volume();
+        assert_eq!(volume.total(&presents), Some(34));
+    }
+
     #[test]
     fn calculate_required_wrapping_paper() {
-        let area = super::calculate_required_wrapping_paper(&get_input("presents").unwrap());
-        assert_eq!(area, 1588178);
+        let area = super::calculate_required_wrapping_paper(
+            &parse_lines::<Present>(&get_input("presents").unwrap()).unwrap(),
+        );
+        assert_eq!(area, Some(1588178));
     }
 
     #[test]
     fn calculate_required_ribbon() {
-        let length = super::calculate_required_ribbon(&get_input("presents").unwrap());
-        assert_eq!(length, 3783758);
+        let length = super::calculate_required_ribbon(
+            &parse_lines::<Present>(&get_input("presents").unwrap()).unwrap(),
+        );
+        assert_eq!(length, Some(3783758));
+    }
+
+    #[test]
+    fn order_report() {
+        let order = "2x3x4\n1x1x10\n3x3x3\n";
+        let mut csv = Vec::new();
+        let report =
+            OrderReport::write_breakdown(order.as_bytes(), &mut csv, Format::Csv, 2).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "line,length,width,height,paper,ribbon\n\
+             1,2,3,4,58,34\n\
+             2,1,1,10,43,14\n\
+             3,3,3,3,63,39\n"
+        );
+        assert_eq!((report.presents, report.paper, report.ribbon), (3, 164, 87));
+        assert_eq!(report.volume_histogram, BTreeMap::from([(8, 1), (16, 2)]));
+        let lines =
+            |lines: &[OrderLine]| -> Vec<_> { lines.iter().map(|line| line.line).collect() };
+        assert_eq!(lines(&report.top_paper), [3, 1]);
+        assert_eq!(lines(&report.top_ribbon), [3, 1]);
+
+        let json = report.to_json();
+        assert_eq!(json["top_paper"][0]["dimensions"], json!([3, 3, 3]));
+        assert_eq!(json["volume_histogram"][1]["presents"], 2);
+
+        let mut json_lines = Vec::new();
+        OrderReport::write_breakdown("2x3x4\n".as_bytes(), &mut json_lines, Format::JsonLines, 1)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(json_lines).unwrap(),
+            "{\"dimensions\":[2,3,4],\"line\":1,\"paper\":58,\"ribbon\":34}\n"
+        );
+    }
+
+    #[test]
+    fn order_report_from_input() {
+        let presents = get_input("presents").unwrap();
+        let report = OrderReport::from_reader(presents.as_bytes(), 3).unwrap();
+        assert_eq!((report.paper, report.ribbon), (1588178, 3783758));
+        assert_eq!(report.presents, 1000);
+        assert_eq!(report.volume_histogram.values().sum::<usize>(), 1000);
+
+        let e = OrderReport::from_reader("1x2x3\n1x2\n".as_bytes(), 3).unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 4: expected a dimension");
+    }
+
+    #[test]
+    fn present_too_large() {
+        // The volume overflows
+        let e = "3000000x3000000x3000000".parse::<Present>().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 1: present is too large, found `3000000x3000000x3000000`"
+        );
+        // The volume fits, but not the paper
+        let e = "4611686018427387904x2x1".parse::<Present>().unwrap_err();
+        assert_eq!(e.column, 1);
+        let e =
+            OrderReport::from_reader("1x1x1\n9223372036854775807x1x1\n".as_bytes(), 1).unwrap_err();
+        assert!(e
+            .to_string()
+            .starts_with("line 2, column 1: present is too large"));
+
+        let present: Present = "2097151x2097151x2097151".parse().unwrap();
+        assert_eq!(present.volume(), 2097151i64.pow(3));
+    }
+
+    #[test]
+    fn totals_too_large() {
+        // Each present fits, but the total ribbon doesn't
+        let order = "2097151x2097151x2097151\n2097151x2097151x2097151\n";
+        let e = OrderReport::from_reader(order.as_bytes(), 1).unwrap_err();
+        assert_eq!(e.to_string(), "line 2, column 1: total ribbon is too large");
+
+        let presents = parse_lines::<Present>(order).unwrap();
+        assert_eq!(Ribbon.total(&presents), None);
+        assert!(Paper.total(&presents).is_some());
+        let day = Day2::parse(order).unwrap();
+        assert_eq!(day.part2(), Answer::None);
     }
 }
@@ -1,9 +1,8 @@
 //! Compile in release mode.
+use crate::parse::{Line, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashSet;
 
-#[allow(dead_code)]
-const MIN_PRESENTS: u32 = 29000000;
-
 #[allow(dead_code)]
 #[derive(Debug)]
 struct House {
@@ -15,7 +14,7 @@ struct House {
 fn get_factors(n: u32) -> FxHashSet<u32> {
     let mut factors = FxHashSet::default();
     for factor in 1..=f32::sqrt(n as f32) as u32 {
-        if n % factor == 0 {
+        if n.is_multiple_of(factor) {
             factors.insert(factor);
             factors.insert(n / factor);
         }
@@ -27,7 +26,7 @@ fn get_factors(n: u32) -> FxHashSet<u32> {
 fn get_factors_not_infinite(n: u32) -> FxHashSet<u32> {
     let mut factors = FxHashSet::default();
     for factor in 1..=f32::sqrt(n as f32) as u32 {
-        if n % factor == 0 {
+        if n.is_multiple_of(factor) {
             if n <= factor * 50 {
                 factors.insert(factor);
             }
@@ -39,37 +38,65 @@ fn get_factors_not_infinite(n: u32) -> FxHashSet<u32> {
     factors
 }
 
+fn find_min_presents_house(
+    get_factors: impl Fn(u32) -> FxHashSet<u32>,
+    presents_per_elf: u32,
+    min_presents: u32,
+) -> u32 {
+    let mut house = House {
+        num: 2,
+        presents: 0,
+    };
+    while {
+        house.presents = get_factors(house.num).iter().sum::<u32>() * presents_per_elf;
+        house.presents < min_presents
+    } {
+        house.num += 1;
+    }
+    house.num
+}
+
+pub struct Day20 {
+    min_presents: u32,
+}
+
+impl Solution for Day20 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let line = Line::single(input.trim());
+        Ok(Self {
+            min_presents: line.parse(line.text, "a number of presents")?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        find_min_presents_house(get_factors, 10, self.min_presents).into()
+    }
+
+    fn part2(&self) -> Answer {
+        find_min_presents_house(get_factors_not_infinite, 11, self.min_presents).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn calculate_min_presents_house() {
-        let mut house = House {
-            num: 2,
-            presents: 0,
-        };
-        while {
-            house.presents = get_factors(house.num).iter().sum::<u32>() * 10;
-            house.presents < MIN_PRESENTS
-        } {
-            house.num += 1;
-        }
-        assert_eq!(house.num, 665280);
+        let min_presents: u32 = get_input("min_presents").unwrap().trim().parse().unwrap();
+        assert_eq!(
+            find_min_presents_house(get_factors, 10, min_presents),
+            665280
+        );
     }
 
     #[test]
     fn calculate_min_presents_house_not_infinite() {
-        let mut house = House {
-            num: 2,
-            presents: 0,
-        };
-        while {
-            house.presents = get_factors_not_infinite(house.num).iter().sum::<u32>() * 11;
-            house.presents < MIN_PRESENTS
-        } {
-            house.num += 1;
-        }
-        assert_eq!(house.num, 705600);
+        let min_presents: u32 = get_input("min_presents").unwrap().trim().parse().unwrap();
+        assert_eq!(
+            find_min_presents_house(get_factors_not_infinite, 11, min_presents),
+            705600
+        );
     }
 }
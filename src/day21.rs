@@ -1,3 +1,6 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
+use std::str::FromStr;
 #[allow(dead_code)]
 #[derive(Debug, PartialEq)]
 struct Item {
@@ -105,14 +108,32 @@ struct Boss {
     armor: i32,
 }
 
-#[allow(dead_code)]
-impl Boss {
-    fn new() -> Self {
-        Self {
-            hitpoints: 100,
-            damage: 8,
-            armor: 2,
+impl FromStr for Boss {
+    type Err = ParseError;
+
+    /// Parse the boss' stats of the form "Hit Points: 100\nDamage: 8\nArmor: 2".
+    fn from_str(boss_str: &str) -> Result<Self, Self::Err> {
+        let mut stats = [None; 3];
+        for line in lines(boss_str) {
+            let (stat, value) = line
+                .text
+                .split_once(": ")
+                .ok_or_else(|| line.error("", "expected `<stat>: <value>`"))?;
+            let i = match stat {
+                "Hit Points" => 0,
+                "Damage" => 1,
+                "Armor" => 2,
+                _ => return Err(line.error(stat, "unknown stat")),
+            };
+            stats[i] = Some(line.parse(value, "a number")?);
         }
+        let last_line = boss_str.lines().count();
+        let missing = |stat| ParseError::new(last_line + 1, "", "", format!("missing {}", stat));
+        Ok(Self {
+            hitpoints: stats[0].ok_or_else(|| missing("Hit Points"))?,
+            damage: stats[1].ok_or_else(|| missing("Damage"))?,
+            armor: stats[2].ok_or_else(|| missing("Armor"))?,
+        })
     }
 }
 
@@ -150,7 +171,7 @@ impl<'a> Player<'a> {
         player
     }
 
-    fn defeats(&self, boss: Boss) -> bool {
+    fn defeats(&self, boss: &Boss) -> bool {
         let player_adjusted_damage = if self.damage - boss.armor <= 0 {
             1
         } else {
@@ -207,84 +228,85 @@ impl<'a> Player<'a> {
     }
 }
 
+/// Brute force every loadout, keeping the ones where the player wins (or loses).
+fn loadouts(boss: &Boss, player_wins: bool) -> Vec<Player<'static>> {
+    let mut loadouts = Vec::new();
+    for weapon in WEAPONS.iter() {
+        for armor in ARMOR.iter() {
+            for right_ring in RINGS.iter() {
+                for left_ring in RINGS
+                    .iter()
+                    .filter(|&left_ring| (left_ring != right_ring) || left_ring.is_none())
+                {
+                    let player = Player::new(
+                        weapon,
+                        armor.as_ref(),
+                        left_ring.as_ref(),
+                        right_ring.as_ref(),
+                    );
+                    if player.defeats(boss) == player_wins {
+                        loadouts.push(player);
+                    }
+                }
+            }
+        }
+    }
+    loadouts
+}
+
+pub struct Day21 {
+    boss: Boss,
+}
+
+impl Solution for Day21 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            boss: input.parse()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        loadouts(&self.boss, true)
+            .iter()
+            .map(|player| player.get_cost_of_gear())
+            .min()
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        loadouts(&self.boss, false)
+            .iter()
+            .map(|player| player.get_cost_of_gear())
+            .max()
+            .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn find_cheapest_gear() {
-        let mut loadouts = Vec::new();
-        // Brute force
-        for weapon in WEAPONS.iter() {
-            for armor in ARMOR.iter() {
-                for right_ring in RINGS.iter() {
-                    for left_ring in RINGS
-                        .iter()
-                        .filter(|&left_ring| (left_ring != right_ring) || *left_ring == None)
-                    {
-                        let player = Player::new(
-                            weapon,
-                            armor.as_ref(),
-                            left_ring.as_ref(),
-                            right_ring.as_ref(),
-                        );
-
-                        let boss = Boss::new();
-                        // Keep loadouts where player wins
-                        if player.defeats(boss) {
-                            loadouts.push(player);
-                        }
-                    }
-                }
-            }
-        }
+        let boss: Boss = get_input("rpg_boss").unwrap().parse().unwrap();
         // Lowest cost loadout that wins
-        assert_eq!(
-            loadouts
-                .into_iter()
-                .min_by_key(|player| player.get_cost_of_gear())
-                .unwrap()
-                .get_cost_of_gear(),
-            91
-        );
+        let cost = loadouts(&boss, true)
+            .iter()
+            .map(|player| player.get_cost_of_gear())
+            .min();
+        assert_eq!(cost, Some(91));
     }
 
     #[test]
     fn find_most_expensive_gear() {
-        let mut loadouts = Vec::new();
-        // Brute force
-        for weapon in WEAPONS.iter() {
-            for armor in ARMOR.iter() {
-                for right_ring in RINGS.iter() {
-                    for left_ring in RINGS
-                        .iter()
-                        .filter(|&left_ring| (left_ring != right_ring) || *left_ring == None)
-                    {
-                        let player = Player::new(
-                            weapon,
-                            armor.as_ref(),
-                            left_ring.as_ref(),
-                            right_ring.as_ref(),
-                        );
-
-                        let boss = Boss::new();
-                        // Keep loadouts where player loses
-                        if !player.defeats(boss) {
-                            loadouts.push(player);
-                        }
-                    }
-                }
-            }
-        }
+        let boss: Boss = get_input("rpg_boss").unwrap().parse().unwrap();
         // Highest cost loadout that loses
-        assert_eq!(
-            loadouts
-                .into_iter()
-                .max_by_key(|player| player.get_cost_of_gear())
-                .unwrap()
-                .get_cost_of_gear(),
-            158
-        );
+        let cost = loadouts(&boss, false)
+            .iter()
+            .map(|player| player.get_cost_of_gear())
+            .max();
+        assert_eq!(cost, Some(158));
     }
 
     #[test]
@@ -302,10 +324,20 @@ mod solution {
             equipped_left_ring: None,
             equipped_right_ring: None,
         };
-        let mut boss = Boss::new();
-        boss.hitpoints = 12;
-        boss.damage = 7;
-        boss.armor = 2;
-        assert!(player.defeats(boss));
+        let boss = Boss {
+            hitpoints: 12,
+            damage: 7,
+            armor: 2,
+        };
+        assert!(player.defeats(&boss));
+    }
+
+    #[test]
+    fn parse_boss_invalid() {
+        let e = "Hit Points: 100\nDamage: x".parse::<Boss>().err().unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 9, "x"));
+
+        let e = "Hit Points: 100\nDamage: 8".parse::<Boss>().err().unwrap();
+        assert_eq!(e.to_string(), "line 3, column 1: missing Armor");
     }
 }
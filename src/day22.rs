@@ -1,5 +1,8 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashSet;
 use std::cmp::Ordering;
+use std::str::FromStr;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -11,14 +14,6 @@ struct Boss {
 
 #[allow(dead_code)]
 impl Boss {
-    fn new() -> Self {
-        Self {
-            hitpoints: 55,
-            damage: 8,
-            poisoned: None,
-        }
-    }
-
     fn new_custom(hitpoints: i32, damage: i32) -> Self {
         Self {
             hitpoints,
@@ -47,6 +42,33 @@ impl Boss {
     }
 }
 
+impl FromStr for Boss {
+    type Err = ParseError;
+
+    /// Parse the boss' stats of the form "Hit Points: 55\nDamage: 8".
+    fn from_str(boss_str: &str) -> Result<Self, Self::Err> {
+        let mut stats = [None; 2];
+        for line in lines(boss_str) {
+            let (stat, value) = line
+                .text
+                .split_once(": ")
+                .ok_or_else(|| line.error("", "expected `<stat>: <value>`"))?;
+            let i = match stat {
+                "Hit Points" => 0,
+                "Damage" => 1,
+                _ => return Err(line.error(stat, "unknown stat")),
+            };
+            stats[i] = Some(line.parse(value, "a number")?);
+        }
+        let last_line = boss_str.lines().count();
+        let missing = |stat| ParseError::new(last_line + 1, "", "", format!("missing {}", stat));
+        Ok(Self::new_custom(
+            stats[0].ok_or_else(|| missing("Hit Points"))?,
+            stats[1].ok_or_else(|| missing("Damage"))?,
+        ))
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct Wizard {
@@ -60,7 +82,7 @@ struct Wizard {
 
 #[allow(dead_code)]
 impl Wizard {
-    fn new() -> Self {
+    fn new(enemy: &Boss) -> Self {
         let mut wizard = Self {
             hitpoints: 50,
             armor: 0,
@@ -69,11 +91,11 @@ impl Wizard {
             recharging: None,
             possible_spells: FxHashSet::default(),
         };
-        wizard.update_possible_spells(&Boss::new());
+        wizard.update_possible_spells(enemy);
         wizard
     }
 
-    fn new_custom(hitpoints: i32, mana: i32) -> Self {
+    fn new_custom(hitpoints: i32, mana: i32, enemy: &Boss) -> Self {
         let mut wizard = Self {
             hitpoints,
             armor: 0,
@@ -82,7 +104,7 @@ impl Wizard {
             recharging: None,
             possible_spells: FxHashSet::default(),
         };
-        wizard.update_possible_spells(&Boss::new());
+        wizard.update_possible_spells(enemy);
         wizard
     }
 
@@ -303,14 +325,14 @@ impl Ord for Battle {
 /// Try 10 iterations of brute force, subsequent iterations will have battles that are taking
 /// too long hence using too much mana.
 #[allow(dead_code)]
-fn find_least_mana_used_battle(hard_mode: bool) -> Vec<Battle> {
+fn find_least_mana_used_battle(boss: &Boss, hard_mode: bool) -> Vec<Battle> {
     // Rotating queue pair
     let mut queue: [Vec<Battle>; 2] = [Vec::new(), Vec::new()];
 
     // Battles the wizard has won
     let mut completed: Vec<Battle> = Vec::new();
 
-    let initial = Battle::new(Wizard::new(), Boss::new(), hard_mode);
+    let initial = Battle::new(Wizard::new(boss), boss.clone(), hard_mode);
     queue[0].push(initial);
 
     let mut i = 0;
@@ -351,26 +373,57 @@ fn find_least_mana_used_battle(hard_mode: bool) -> Vec<Battle> {
     completed
 }
 
+pub struct Day22 {
+    boss: Boss,
+}
+
+impl Solution for Day22 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            boss: input.parse()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        find_least_mana_used_battle(&self.boss, false)
+            .into_iter()
+            .min()
+            .map(|battle| battle.mana_used)
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        find_least_mana_used_battle(&self.boss, true)
+            .into_iter()
+            .min()
+            .map(|battle| battle.mana_used)
+            .into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn find_least_mana_used_battle() {
-        let completed = super::find_least_mana_used_battle(false);
+        let boss: Boss = get_input("wizard_boss").unwrap().parse().unwrap();
+        let completed = super::find_least_mana_used_battle(&boss, false);
         assert_eq!(completed.into_iter().min().unwrap().mana_used, 953);
     }
 
     #[test]
     fn find_least_mana_used_battle_hard() {
-        let completed = super::find_least_mana_used_battle(true);
+        let boss: Boss = get_input("wizard_boss").unwrap().parse().unwrap();
+        let completed = super::find_least_mana_used_battle(&boss, true);
         assert_eq!(completed.into_iter().min().unwrap().mana_used, 1289);
     }
 
     #[test]
     fn battle_simple() {
-        let wizard = Wizard::new_custom(10, 250);
         let boss = Boss::new_custom(14, 8);
+        let wizard = Wizard::new_custom(10, 250, &boss);
         let mut battle = Battle::new(wizard, boss, false);
         for spell in [
             Spell::Recharge,
@@ -1,3 +1,5 @@
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
 use std::str::FromStr;
 
 /// u8 = reg, i32 = offset
@@ -16,41 +18,44 @@ struct Program {
     instructions: Vec<Instruction>,
 }
 
+/// Parse a register operand, which may be followed by a comma e.g. "a,".
+fn parse_register<'a>(
+    line: &Line<'a>,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<u8, ParseError> {
+    let token = line.expect(tokens.next(), "a register")?;
+    match token.trim_end_matches(',') {
+        "a" => Ok(b'a'),
+        "b" => Ok(b'b'),
+        _ => Err(line.error(token, "expected register `a` or `b`")),
+    }
+}
+
 impl FromStr for Program {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(instructions_str: &str) -> Result<Self, Self::Err> {
         let mut instructions = Vec::new();
-        for instruction in instructions_str.lines() {
-            if instruction.starts_with("hlf") {
-                let mut instruction_split = instruction.split(" ");
-                let reg = instruction_split.nth(1).unwrap().as_bytes()[0];
-                instructions.push(Instruction::Hlf(reg));
-            } else if instruction.starts_with("tpl") {
-                let mut instruction_split = instruction.split(" ");
-                let reg = instruction_split.nth(1).unwrap().as_bytes()[0];
-                instructions.push(Instruction::Tpl(reg));
-            } else if instruction.starts_with("inc") {
-                let mut instruction_split = instruction.split(" ");
-                let reg = instruction_split.nth(1).unwrap().as_bytes()[0];
-                instructions.push(Instruction::Inc(reg));
-            } else if instruction.starts_with("jmp") {
-                let mut instruction_split = instruction.split(" ");
-                let offset = instruction_split.nth(1).unwrap().parse::<i32>().unwrap();
-                instructions.push(Instruction::Jmp(offset));
-            } else if instruction.starts_with("jie") {
-                let mut instruction_split = instruction.split(" ");
-                let reg = instruction_split.nth(1).unwrap().as_bytes()[0];
-                let offset = instruction_split.next().unwrap().parse::<i32>().unwrap();
-                instructions.push(Instruction::Jie(reg, offset));
-            } else if instruction.starts_with("jio") {
-                let mut instruction_split = instruction.split(" ");
-                let reg = instruction_split.nth(1).unwrap().as_bytes()[0];
-                let offset = instruction_split.next().unwrap().parse::<i32>().unwrap();
-                instructions.push(Instruction::Jio(reg, offset));
-            } else {
-                unreachable!();
-            }
+        for line in lines(instructions_str) {
+            let mut tokens = line.text.split(' ');
+            let opcode = line.expect(tokens.next(), "an instruction")?;
+            let instruction = match opcode {
+                "hlf" => Instruction::Hlf(parse_register(&line, &mut tokens)?),
+                "tpl" => Instruction::Tpl(parse_register(&line, &mut tokens)?),
+                "inc" => Instruction::Inc(parse_register(&line, &mut tokens)?),
+                "jmp" => Instruction::Jmp(line.parse_next(&mut tokens, "an offset")?),
+                "jie" => Instruction::Jie(
+                    parse_register(&line, &mut tokens)?,
+                    line.parse_next(&mut tokens, "an offset")?,
+                ),
+                "jio" => Instruction::Jio(
+                    parse_register(&line, &mut tokens)?,
+                    line.parse_next(&mut tokens, "an offset")?,
+                ),
+                _ => return Err(line.error(opcode, "unknown instruction")),
+            };
+            line.expect_end(&mut tokens)?;
+            instructions.push(instruction);
         }
         Ok(Program { instructions })
     }
@@ -93,8 +98,8 @@ impl Program {
                 }
                 Instruction::Jie(reg, offset) => {
                     let is_even = match reg {
-                        b'a' => a % 2 == 0,
-                        b'b' => b % 2 == 0,
+                        b'a' => a.is_multiple_of(2),
+                        b'b' => b.is_multiple_of(2),
                         _ => unreachable!(),
                     };
                     if is_even {
@@ -120,6 +125,26 @@ impl Program {
     }
 }
 
+pub struct Day23 {
+    program: Program,
+}
+
+impl Solution for Day23 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            program: input.parse()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.program.execute(0).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.program.execute(1).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -140,4 +165,19 @@ mod solution {
         let b = program.execute(1);
         assert_eq!(b, 231);
     }
+
+    #[test]
+    fn parse_program_invalid() {
+        let e = "inc a\njio c, +19".parse::<Program>().err().unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 5, "c,"));
+
+        let e = "inc a\nmul a".parse::<Program>().err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: unknown instruction, found `mul`"
+        );
+
+        let e = "jmp".parse::<Program>().err().unwrap();
+        assert_eq!(e.to_string(), "line 1, column 4: expected an offset");
+    }
 }
@@ -1,3 +1,5 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 
 #[allow(dead_code)]
@@ -15,6 +17,40 @@ fn get_group_set(packages: &[i64], group_weight: i64, group_size: usize) -> Vec<
         .collect()
 }
 
+/// Find the smallest group size with a group of the right weight, and return the lowest
+/// quantum entanglement among those groups.
+fn lowest_quantum_entanglement(packages: &[i64], groups: i64) -> Option<i64> {
+    let group_weight = packages.iter().sum::<i64>() / groups;
+    (1..=packages.len())
+        .map(|group_size| get_group_set(packages, group_weight, group_size))
+        .find(|group_set| !group_set.is_empty())?
+        .into_iter()
+        .map(|g| g.iter().product::<i64>())
+        .min()
+}
+
+pub struct Day24 {
+    packages: Vec<i64>,
+}
+
+impl Solution for Day24 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            packages: lines(input)
+                .map(|line| line.parse(line.text, "a package weight"))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        lowest_quantum_entanglement(&self.packages, 3).into()
+    }
+
+    fn part2(&self) -> Answer {
+        lowest_quantum_entanglement(&self.packages, 4).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -22,22 +58,10 @@ mod solution {
 
     #[test]
     fn balance_sleigh_simple() {
-        let min_group_size = 2;
-        let group_set = get_group_set(&PACKAGES_SIMPLE, GROUP_WEIGHT_SIMPLE, min_group_size);
-
-        let smallest_group_len = group_set.iter().min_by_key(|g| g.len()).unwrap().len();
-        assert_eq!(smallest_group_len, min_group_size);
-
-        // Get lowest quantum entanglement
-        assert_eq!(
-            group_set
-                .into_iter()
-                .filter(|g| g.len() == smallest_group_len)
-                .map(|g| g.iter().product::<i64>())
-                .min()
-                .unwrap(),
-            99
-        );
+        // The smallest group of the right weight has two packages
+        assert!(get_group_set(&PACKAGES_SIMPLE, GROUP_WEIGHT_SIMPLE, 1).is_empty());
+        assert!(!get_group_set(&PACKAGES_SIMPLE, GROUP_WEIGHT_SIMPLE, 2).is_empty());
+        assert_eq!(lowest_quantum_entanglement(&PACKAGES_SIMPLE, 3), Some(99));
     }
 
     #[test]
@@ -47,25 +71,9 @@ mod solution {
             .lines()
             .map(|p| p.parse().unwrap())
             .collect();
-        let total_weight: i64 = packages.iter().sum();
-        let group_weight = total_weight / 3;
-        let min_group_size = 6;
-
-        let group_set = get_group_set(&packages, group_weight, min_group_size);
-
-        let smallest_group_len = group_set.iter().min_by_key(|g| g.len()).unwrap().len();
-        assert_eq!(smallest_group_len, min_group_size);
-
-        // Get lowest quantum entanglement
-        assert_eq!(
-            group_set
-                .into_iter()
-                .filter(|g| g.len() == smallest_group_len)
-                .map(|g| g.iter().product::<i64>())
-                .min()
-                .unwrap(),
-            11266889531
-        );
+        // The smallest group of the right weight has six packages
+        assert!(get_group_set(&packages, packages.iter().sum::<i64>() / 3, 5).is_empty());
+        assert_eq!(lowest_quantum_entanglement(&packages, 3), Some(11266889531));
     }
 
     #[test]
@@ -75,24 +83,8 @@ mod solution {
             .lines()
             .map(|p| p.parse().unwrap())
             .collect();
-        let total_weight: i64 = packages.iter().sum();
-        let group_weight = total_weight / 4;
-        let min_group_size = 5;
-
-        let group_set = get_group_set(&packages, group_weight, min_group_size);
-
-        let smallest_group_len = group_set.iter().min_by_key(|g| g.len()).unwrap().len();
-        assert_eq!(smallest_group_len, min_group_size);
-
-        // Get lowest quantum entanglement
-        assert_eq!(
-            group_set
-                .into_iter()
-                .filter(|g| g.len() == smallest_group_len)
-                .map(|g| g.iter().product::<i64>())
-                .min()
-                .unwrap(),
-            77387711
-        );
+        // The smallest group of the right weight has five packages
+        assert!(get_group_set(&packages, packages.iter().sum::<i64>() / 4, 4).is_empty());
+        assert_eq!(lowest_quantum_entanglement(&packages, 4), Some(77387711));
     }
 }
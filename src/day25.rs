@@ -1,4 +1,8 @@
-/// Convert grid coord to position index.
+use crate::parse::{Line, ParseError};
+use crate::solution::{Answer, Solution};
+use std::num::NonZeroUsize;
+
+/// Convert grid coord to position index. Rows and columns start at 1.
 #[allow(dead_code)]
 fn grid_to_index(row: usize, col: usize) -> usize {
     let mut index = 0;
@@ -17,20 +21,77 @@ fn grid_to_index(row: usize, col: usize) -> usize {
     index
 }
 
+/// Parse the row and column out of
+/// "To continue, please consult the code grid in the manual.  Enter the code at row 2947, column 3029."
+/// The grid starts at row 1, column 1.
+fn parse_grid_coord(manual: &str) -> Result<(usize, usize), ParseError> {
+    let line = Line::single(manual.trim_end());
+    let (_, coord) = line
+        .text
+        .split_once("row ")
+        .ok_or_else(|| line.error("", "expected `row <n>`"))?;
+    let (row, col) = coord
+        .split_once(", column ")
+        .ok_or_else(|| line.error("", "expected `, column <n>`"))?;
+    let col = col.trim_end_matches('.');
+    let row: NonZeroUsize = line.parse(row, "a positive row")?;
+    let col: NonZeroUsize = line.parse(col, "a positive column")?;
+    Ok((row.get(), col.get()))
+}
+
+fn calculate_code(index: usize) -> u64 {
+    // Initial code
+    let mut code: u64 = 20151125;
+    for _ in 1..index {
+        // Apply arithmetic
+        code = (code * 252533) % 33554393;
+    }
+    code
+}
+
+pub struct Day25 {
+    row: usize,
+    col: usize,
+}
+
+impl Solution for Day25 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (row, col) = parse_grid_coord(input)?;
+        Ok(Self { row, col })
+    }
+
+    fn part1(&self) -> Answer {
+        calculate_code(grid_to_index(self.row, self.col)).into()
+    }
+
+    /// Day 25 only has one part.
+    fn part2(&self) -> Answer {
+        Answer::None
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
 
     #[test]
     fn calculate_weather_machine_code() {
-        let index = grid_to_index(2947, 3029);
+        let (row, col) = parse_grid_coord(&get_input("code_grid").unwrap()).unwrap();
+        assert_eq!((row, col), (2947, 3029));
+        let index = grid_to_index(row, col);
+        assert_eq!(calculate_code(index), 19980801);
+    }
 
-        // Initial code
-        let mut code: u64 = 20151125;
-        for _ in 1..index {
-            // Apply arithmetic
-            code = (code * 252533) % 33554393;
-        }
-        assert_eq!(code, 19980801);
+    #[test]
+    fn parse_grid_coord_zero() {
+        let e = parse_grid_coord("Enter the code at row 0, column 3.").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 23: expected a positive row, found `0`"
+        );
+
+        let e = parse_grid_coord("Enter the code at row 2, column 0.").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (1, 33, "0"));
     }
 }
@@ -1,55 +1,210 @@
-use rustc_hash::FxHashSet;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+use rustc_hash::FxHashMap;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
 
-#[allow(dead_code)]
-fn count_unique_houses_delivered(directions: &str) -> i64 {
-    let mut current_pos: (i64, i64) = (0, 0);
-    let mut delivered_houses = FxHashSet::default();
-    delivered_houses.insert(current_pos);
-    let mut count = 1;
-
-    for c in directions.bytes() {
-        match c {
-            b'^' => current_pos.1 += 1,
-            b'v' => current_pos.1 -= 1,
-            b'>' => current_pos.0 += 1,
-            b'<' => current_pos.0 -= 1,
-            _ => (),
+pub type House = (i64, i64);
+
+/// Decides which Santa follows each direction.
+pub enum Assignment {
+    /// Santas take turns.
+    RoundRobin,
+    /// Each Santa follows a block of this many directions before handing over to the next.
+    Blocks(NonZeroUsize),
+    /// Maps (direction index, number of Santas) to a Santa.
+    Custom(Box<dyn Fn(usize, usize) -> usize>),
+}
+
+/// Simulates a fleet of Santas, all starting at the same house, splitting the directions between
+/// them.
+pub struct Delivery {
+    santas: NonZeroUsize,
+    assignment: Assignment,
+}
+
+impl Delivery {
+    /// `santas` Santas taking turns.
+    pub fn new(santas: NonZeroUsize) -> Self {
+        Self {
+            santas,
+            assignment: Assignment::RoundRobin,
+        }
+    }
+
+    pub fn with_assignment(mut self, assignment: Assignment) -> Self {
+        self.assignment = assignment;
+        self
+    }
+
+    pub fn santa_for(&self, i: usize) -> usize {
+        match &self.assignment {
+            Assignment::RoundRobin => i % self.santas,
+            Assignment::Blocks(len) => (i / *len) % self.santas,
+            Assignment::Custom(assign) => assign(i, self.santas.get()) % self.santas,
         }
-        let not_visited = delivered_houses.insert(current_pos);
-        if not_visited {
-            count += 1;
+    }
+
+    /// The number of presents delivered to each house. Every Santa delivers one at the start.
+    pub fn visits(&self, directions: &str) -> FxHashMap<House, usize> {
+        let mut positions = vec![(0, 0); self.santas.get()];
+        let mut visits = FxHashMap::default();
+        visits.insert((0, 0), self.santas.get());
+
+        for (i, c) in directions.bytes().enumerate() {
+            let position = &mut positions[self.santa_for(i)];
+            match c {
+                b'^' => position.1 += 1,
+                b'v' => position.1 -= 1,
+                b'>' => position.0 += 1,
+                b'<' => position.0 -= 1,
+                _ => continue,
+            }
+            *visits.entry(*position).or_default() += 1;
         }
+        visits
     }
-    count
 }
 
-#[allow(dead_code)]
-fn count_unique_houses_delivered_with_robo_santa(directions: &str) -> i64 {
-    let mut current_pos: [(i64, i64); 2] = [(0, 0); 2];
-    let mut delivered_houses = FxHashSet::default();
-    delivered_houses.insert(current_pos[0]);
-    let mut count = 1;
-
-    for (i, c) in directions.bytes().enumerate() {
-        match c {
-            b'^' => current_pos[i % 2].1 += 1,
-            b'v' => current_pos[i % 2].1 -= 1,
-            b'>' => current_pos[i % 2].0 += 1,
-            b'<' => current_pos[i % 2].0 -= 1,
-            _ => (),
+/// The houses visited by a delivery and how many presents each got, within the bounding box of
+/// the route.
+pub struct VisitMap {
+    visits: FxHashMap<House, usize>,
+    min: House,
+    max: House,
+}
+
+impl VisitMap {
+    pub fn new(visits: FxHashMap<House, usize>) -> Self {
+        let mut min = (0, 0);
+        let mut max = (0, 0);
+        for &(x, y) in visits.keys() {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
         }
-        let not_visited = delivered_houses.insert(current_pos[i % 2]);
-        if not_visited {
-            count += 1;
+        Self { visits, min, max }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.0 - self.min.0 + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.1 - self.min.1 + 1) as usize
+    }
+
+    pub fn get(&self, house: House) -> usize {
+        self.visits.get(&house).copied().unwrap_or(0)
+    }
+
+    /// Visit counts row by row, north at the top.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = usize> + '_> + '_ {
+        (self.min.1..=self.max.1)
+            .rev()
+            .map(move |y| (self.min.0..=self.max.0).map(move |x| self.get((x, y))))
+    }
+
+    /// One character per house: `.` if unvisited, the number of presents up to 9, else `+`.
+    pub fn to_ascii(&self) -> String {
+        let mut map = String::with_capacity((self.width() + 1) * self.height());
+        for row in self.rows() {
+            map.extend(row.map(|visits| match visits {
+                0 => '.',
+                1..=9 => char::from_digit(visits as u32, 10).unwrap(),
+                _ => '+',
+            }));
+            map.push('\n');
         }
+        map
+    }
+
+    /// Heat in [0, 1], log-scaled so houses visited once still show up. 0 if unvisited.
+    fn heat(&self, visits: usize, max_visits: usize) -> f64 {
+        if visits == 0 {
+            return 0.0;
+        }
+        ((visits as f64).ln() + 1.0) / ((max_visits as f64).ln() + 1.0)
+    }
+
+    /// Binary PGM (P5) heatmap, one pixel per house, brighter for more presents.
+    pub fn write_pgm(&self, mut out: impl Write) -> io::Result<()> {
+        let max_visits = self.visits.values().copied().max().unwrap_or(0);
+        write!(out, "P5\n{} {}\n255\n", self.width(), self.height())?;
+        for row in self.rows() {
+            let pixels: Vec<u8> = row
+                .map(|visits| (self.heat(visits, max_visits) * 255.0).round() as u8)
+                .collect();
+            out.write_all(&pixels)?;
+        }
+        Ok(())
+    }
+
+    /// Binary PPM (P6) heatmap, one pixel per house, black if unvisited and from blue to red as
+    /// the number of presents grows.
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        let max_visits = self.visits.values().copied().max().unwrap_or(0);
+        write!(out, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        for row in self.rows() {
+            let mut pixels = Vec::with_capacity(self.width() * 3);
+            for visits in row {
+                if visits == 0 {
+                    pixels.extend([0, 0, 0]);
+                    continue;
+                }
+                let heat = self.heat(visits, max_visits);
+                pixels.extend([
+                    (heat * 255.0).round() as u8,
+                    0,
+                    ((1.0 - heat) * 255.0).round() as u8,
+                ]);
+            }
+            out.write_all(&pixels)?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+fn count_unique_houses_delivered(directions: &str) -> i64 {
+    Delivery::new(NonZeroUsize::MIN).visits(directions).len() as i64
+}
+
+#[allow(dead_code)]
+fn count_unique_houses_delivered_with_robo_santa(directions: &str) -> i64 {
+    Delivery::new(NonZeroUsize::new(2).unwrap())
+        .visits(directions)
+        .len() as i64
+}
+
+pub struct Day3 {
+    directions: String,
+}
+
+impl Solution for Day3 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            directions: input.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        count_unique_houses_delivered(&self.directions).into()
+    }
+
+    fn part2(&self) -> Answer {
+        count_unique_houses_delivered_with_robo_santa(&self.directions).into()
     }
-    count
 }
 
 #[cfg(test)]
 mod solution {
+    use super::*;
     use crate::input::get_input::get_input;
 
+    fn nonzero(n: usize) -> NonZeroUsize {
+        NonZeroUsize::new(n).unwrap()
+    }
+
     #[test]
     fn count_unique_houses_delivered() {
         let directions = get_input("house_directions").unwrap();
@@ -63,4 +218,102 @@ mod solution {
         let count = super::count_unique_houses_delivered_with_robo_santa(&directions);
         assert_eq!(count, 2341);
     }
+
+    #[test]
+    fn delivery_visits() {
+        let visits = Delivery::new(nonzero(2)).visits("^v^v\n");
+        assert_eq!(
+            visits,
+            FxHashMap::from_iter([
+                ((0, 0), 2),
+                ((0, 1), 1),
+                ((0, 2), 1),
+                ((0, -1), 1),
+                ((0, -2), 1)
+            ])
+        );
+
+        let blocks = Delivery::new(nonzero(2)).with_assignment(Assignment::Blocks(nonzero(2)));
+        assert_eq!(
+            blocks.visits("^^v<"),
+            FxHashMap::from_iter([
+                ((0, 0), 2),
+                ((0, 1), 1),
+                ((0, 2), 1),
+                ((0, -1), 1),
+                ((-1, -1), 1)
+            ])
+        );
+
+        // Santa 0 delivers everything but the third direction
+        let custom =
+            Delivery::new(nonzero(3)).with_assignment(Assignment::Custom(Box::new(|i, _| {
+                if i == 2 {
+                    1
+                } else {
+                    0
+                }
+            })));
+        assert_eq!(
+            custom.visits(">>>"),
+            FxHashMap::from_iter([((0, 0), 3), ((1, 0), 2), ((2, 0), 1)])
+        );
+    }
+
+    #[test]
+    fn compare_fleet_sizes() {
+        let directions = get_input("house_directions").unwrap();
+        let unique_houses: Vec<_> = (1..=4)
+            .map(|santas| Delivery::new(nonzero(santas)).visits(&directions).len())
+            .collect();
+        assert_eq!(unique_houses[..2], [2081, 2341]);
+
+        let presents: usize = Delivery::new(nonzero(3)).visits(&directions).values().sum();
+        assert_eq!(presents, 3 + directions.trim_end().len());
+    }
+
+    #[test]
+    fn visit_map_ascii() {
+        let map = VisitMap::new(Delivery::new(nonzero(1)).visits("^>v<<<"));
+        assert_eq!((map.width(), map.height()), (4, 2));
+        assert_eq!(map.to_ascii(), "..11\n1121\n");
+
+        let map = VisitMap::new(Delivery::new(nonzero(1)).visits(&"^v".repeat(9)));
+        assert_eq!(map.to_ascii(), "9\n+\n");
+    }
+
+    #[test]
+    fn visit_map_heatmaps() {
+        let map = VisitMap::new(Delivery::new(nonzero(1)).visits("^>v<<<"));
+        let mut pgm = Vec::new();
+        map.write_pgm(&mut pgm).unwrap();
+        let header = b"P5\n4 2\n255\n";
+        assert_eq!(&pgm[..header.len()], header);
+        // Houses visited once are dimmer than those visited twice
+        let once = (255.0 / (2f64.ln() + 1.0)).round() as u8;
+        assert_eq!(
+            &pgm[header.len()..],
+            [0, 0, once, once, once, once, 255, once]
+        );
+
+        let mut ppm = Vec::new();
+        map.write_ppm(&mut ppm).unwrap();
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 4 * 2 * 3);
+        assert_eq!(&ppm[header.len()..header.len() + 3], [0, 0, 0]);
+        // The starting house is the most visited
+        let start = header.len() + 6 * 3;
+        assert_eq!(&ppm[start..start + 3], [255, 0, 0]);
+    }
+
+    #[test]
+    fn single_direction_blocks() {
+        let blocks = Delivery::new(nonzero(3)).with_assignment(Assignment::Blocks(nonzero(1)));
+        let directions = "^>v<<^^>";
+        assert_eq!(
+            blocks.visits(directions),
+            Delivery::new(nonzero(3)).visits(directions)
+        );
+    }
 }
@@ -1,17 +1,26 @@
-use md5::{Digest, Md5};
+use crate::parse::{Line, ParseError};
+use crate::solution::{Answer, Solution};
+use digest::Digest;
+use md5::Md5;
+use rayon::prelude::*;
+use std::collections::VecDeque;
 
-#[allow(dead_code)]
-const SECRET: &str = "ckczppom";
+/// Nonces tried in a row by one thread.
+const BLOCK: u64 = 4096;
 
+/// Find the first nonce whose hash, with any `Digest` e.g. `Md5`, meets the condition.
 #[allow(dead_code)]
-fn find_first_hash_with_condition(secret: &str, condition: impl Fn(&[u8]) -> bool) -> u64 {
+fn find_first_hash_with_condition<D: Digest>(
+    secret: &str,
+    condition: impl Fn(&[u8]) -> bool,
+) -> u64 {
     let mut count: u64 = 0;
     let mut msg = String::from(secret);
 
     while {
         msg.push_str(&count.to_string());
 
-        let mut hasher = Md5::new();
+        let mut hasher = D::new();
         hasher.update(msg.as_bytes());
         let hash = hasher.finalize();
 
@@ -23,21 +32,323 @@ fn find_first_hash_with_condition(secret: &str, condition: impl Fn(&[u8]) -> boo
     count
 }
 
+/// Write `n` in decimal to the end of `buffer`, returning the digits.
+fn format_nonce(mut n: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut i = buffer.len();
+    loop {
+        i -= 1;
+        buffer[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buffer[i..];
+        }
+    }
+}
+
+/// The first nonce in `nonces` whose hash meets the condition. `prefix` has already hashed the
+/// secret, so only the nonce is hashed for each try.
+fn search_block<D: Digest + Clone>(
+    prefix: &D,
+    nonces: std::ops::Range<u64>,
+    condition: &impl Fn(&[u8]) -> bool,
+) -> Option<u64> {
+    let mut buffer = [0; 20];
+    nonces.into_iter().find(|&nonce| {
+        let mut hasher = prefix.clone();
+        hasher.update(format_nonce(nonce, &mut buffer));
+        condition(&hasher.finalize())
+    })
+}
+
+/// Find the lowest nonce whose hash meets the condition, searching blocks of nonces on the rayon
+/// thread pool. Blocks are searched in rounds, and the first block of a round with a match holds
+/// the lowest nonce, since every earlier block has been searched.
+pub fn mine_lowest_nonce<D: Digest + Clone + Sync>(
+    secret: &str,
+    condition: impl Fn(&[u8]) -> bool + Sync,
+) -> u64 {
+    let prefix = D::new_with_prefix(secret.as_bytes());
+    let blocks_per_round = rayon::current_num_threads() as u64 * 4;
+    let mut start = 0;
+    loop {
+        let found = (0..blocks_per_round)
+            .into_par_iter()
+            .find_map_first(|block| {
+                let block_start = start + block * BLOCK;
+                search_block(&prefix, block_start..block_start + BLOCK, &condition)
+            });
+        if let Some(nonce) = found {
+            return nonce;
+        }
+        start += blocks_per_round * BLOCK;
+    }
+}
+
+/// A condition on the leading hex digits (nibbles) of a hash: each byte of the hash, masked,
+/// must equal the expected value. Only the bytes with a non-zero mask are checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashCondition {
+    mask: Vec<u8>,
+    value: Vec<u8>,
+}
+
+impl HashCondition {
+    /// Matches any hash.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The hash starts with `n` zeros in hex.
+    pub fn leading_zeros(n: usize) -> Self {
+        (0..n).fold(Self::new(), |condition, i| condition.nibble(i, 0xf, 0))
+    }
+
+    /// The hash starts with the given hex digits, e.g. "abc".
+    pub fn hex_prefix(prefix: &str) -> Result<Self, ParseError> {
+        let line = Line::single(prefix);
+        prefix
+            .char_indices()
+            .try_fold(Self::new(), |condition, (i, c)| {
+                let digit = c.to_digit(16).ok_or_else(|| {
+                    line.error(&prefix[i..i + c.len_utf8()], "expected a hex digit")
+                })?;
+                Ok(condition.nibble(i, 0xf, digit as u8))
+            })
+    }
+
+    /// The `index`th hex digit of the hash (0 is the high nibble of the first byte), masked with
+    /// the low 4 bits of `mask`, equals `value`.
+    pub fn nibble(mut self, index: usize, mask: u8, value: u8) -> Self {
+        let byte = index / 2;
+        if self.mask.len() <= byte {
+            self.mask.resize(byte + 1, 0);
+            self.value.resize(byte + 1, 0);
+        }
+        let shift = if index.is_multiple_of(2) { 4 } else { 0 };
+        let mask = (mask & 0xf) << shift;
+        self.mask[byte] |= mask;
+        self.value[byte] = (self.value[byte] & !mask) | ((value << shift) & mask);
+        self
+    }
+
+    #[inline(always)]
+    pub fn matches(&self, hash: &[u8]) -> bool {
+        hash.len() >= self.mask.len()
+            && self
+                .mask
+                .iter()
+                .zip(&self.value)
+                .zip(hash)
+                .all(|((mask, value), byte)| byte & mask == *value)
+    }
+}
+
+/// Every nonce whose hash meets the condition, in increasing order. Nonces are searched a round
+/// of blocks at a time on the rayon thread pool.
+pub struct MatchingNonces<D: Digest + Clone + Sync> {
+    prefix: D,
+    condition: HashCondition,
+    start: u64,
+    found: VecDeque<u64>,
+}
+
+impl<D: Digest + Clone + Sync> MatchingNonces<D> {
+    pub fn new(secret: &str, condition: HashCondition) -> Self {
+        Self {
+            prefix: D::new_with_prefix(secret.as_bytes()),
+            condition,
+            start: 0,
+            found: VecDeque::new(),
+        }
+    }
+}
+
+impl<D: Digest + Clone + Sync> Iterator for MatchingNonces<D> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.found.is_empty() {
+            let blocks_per_round = rayon::current_num_threads() as u64 * 4;
+            let start = self.start;
+            let (prefix, condition) = (&self.prefix, &self.condition);
+            self.found = (0..blocks_per_round)
+                .into_par_iter()
+                .flat_map_iter(|block| {
+                    let block_start = start + block * BLOCK;
+                    let mut buffer = [0; 20];
+                    (block_start..block_start + BLOCK).filter(move |&nonce| {
+                        let mut hasher = prefix.clone();
+                        hasher.update(format_nonce(nonce, &mut buffer));
+                        condition.matches(&hasher.finalize())
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into();
+            self.start += blocks_per_round * BLOCK;
+        }
+        self.found.pop_front()
+    }
+}
+
+pub struct Day4 {
+    secret: String,
+}
+
+impl Solution for Day4 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            secret: input.trim().to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let condition = HashCondition::leading_zeros(5);
+        mine_lowest_nonce::<Md5>(&self.secret, |h| condition.matches(h)).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let condition = HashCondition::leading_zeros(6);
+        mine_lowest_nonce::<Md5>(&self.secret, |h| condition.matches(h)).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
+    use crate::input::get_input::get_input;
+    use sha1::Sha1;
+    use sha2::Sha256;
 
     #[test]
     fn find_first_hash_with_five_zeroes() {
-        let count =
-            super::find_first_hash_with_condition(SECRET, |h| h[0] == 0 && h[1] == 0 && h[2] < 16);
+        let secret = get_input("secret").unwrap();
+        let count = super::find_first_hash_with_condition::<Md5>(secret.trim(), |h| {
+            h[0] == 0 && h[1] == 0 && h[2] < 16
+        });
         assert_eq!(count, 117946);
     }
 
     #[test]
     fn find_first_hash_with_six_zeroes() {
-        let count =
-            super::find_first_hash_with_condition(SECRET, |h| h[0] == 0 && h[1] == 0 && h[2] == 0);
+        let secret = get_input("secret").unwrap();
+        let count = super::find_first_hash_with_condition::<Md5>(secret.trim(), |h| {
+            h[0] == 0 && h[1] == 0 && h[2] == 0
+        });
         assert_eq!(count, 3938038);
     }
+
+    #[test]
+    fn format_nonce_digits() {
+        let mut buffer = [0; 20];
+        assert_eq!(format_nonce(0, &mut buffer), b"0");
+        assert_eq!(format_nonce(3938038, &mut buffer), b"3938038");
+        assert_eq!(format_nonce(u64::MAX, &mut buffer), b"18446744073709551615");
+    }
+
+    #[test]
+    fn mine_lowest_nonce_matches_sequential() {
+        let secret = get_input("secret").unwrap();
+        let secret = secret.trim();
+        assert_eq!(
+            mine_lowest_nonce::<Md5>(secret, |h| h[0] == 0 && h[1] == 0 && h[2] < 16),
+            117946
+        );
+
+        // Conditions that match in many blocks of a round must still give the lowest nonce
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        for condition in [
+            |h: &[u8]| h[0] == 0,
+            |h: &[u8]| h[0] < 128,
+            |h: &[u8]| h[0] == 7 && h[1] < 64,
+        ] {
+            let sequential = find_first_hash_with_condition::<Md5>(secret, condition);
+            assert_eq!(
+                pool.install(|| mine_lowest_nonce::<Md5>(secret, condition)),
+                sequential
+            );
+        }
+    }
+
+    #[test]
+    fn hash_condition() {
+        let five_zeros = HashCondition::leading_zeros(5);
+        assert_eq!(five_zeros.mask, [0xff, 0xff, 0xf0]);
+        assert!(five_zeros.matches(&[0, 0, 0x0f, 0xff]));
+        assert!(!five_zeros.matches(&[0, 0, 0x10, 0]));
+        assert!(!five_zeros.matches(&[0, 0]));
+
+        let abc = HashCondition::hex_prefix("abC").unwrap();
+        assert!(abc.matches(&[0xab, 0xc7]));
+        assert!(!abc.matches(&[0xab, 0xd7]));
+        let e = HashCondition::hex_prefix("0x1").unwrap_err();
+        assert_eq!((e.column, e.token.as_str()), (2, "x"));
+
+        // Odd third hex digit, and the fourth is 0xa
+        let masked = HashCondition::new().nibble(2, 0x1, 0x1).nibble(3, 0xf, 0xa);
+        assert!(masked.matches(&[0x12, 0x3a]));
+        assert!(masked.matches(&[0x00, 0xfa]));
+        assert!(!masked.matches(&[0x12, 0x2a]));
+        assert!(HashCondition::new().matches(&[]));
+    }
+
+    #[test]
+    fn matching_nonces_in_order() {
+        let secret = get_input("secret").unwrap();
+        let secret = secret.trim();
+        let condition = HashCondition::hex_prefix("00").unwrap();
+        let expected: Vec<u64> = (0..)
+            .filter(|&nonce| {
+                let hash = Md5::digest(format!("{}{}", secret, nonce));
+                condition.matches(&hash)
+            })
+            .take(50)
+            .collect();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        let nonces: Vec<u64> = pool.install(|| {
+            MatchingNonces::<Md5>::new(secret, condition)
+                .take(50)
+                .collect()
+        });
+        assert_eq!(nonces, expected);
+
+        let mut five_zeros = MatchingNonces::<Md5>::new(secret, HashCondition::leading_zeros(5));
+        assert_eq!(five_zeros.next(), Some(117946));
+    }
+
+    /// The nonces found with a digest, checked against a naive search.
+    fn check_digest<D: Digest + Clone + Sync>(secret: &str, condition: &HashCondition) -> Vec<u64> {
+        let expected: Vec<u64> = (0..)
+            .filter(|&nonce| condition.matches(&D::digest(format!("{}{}", secret, nonce))))
+            .take(3)
+            .collect();
+        assert_eq!(
+            find_first_hash_with_condition::<D>(secret, |h| condition.matches(h)),
+            expected[0]
+        );
+        assert_eq!(
+            mine_lowest_nonce::<D>(secret, |h| condition.matches(h)),
+            expected[0]
+        );
+        let nonces: Vec<u64> = MatchingNonces::<D>::new(secret, condition.clone())
+            .take(3)
+            .collect();
+        assert_eq!(nonces, expected);
+        nonces
+    }
+
+    #[test]
+    fn mine_with_other_digests() {
+        let condition = HashCondition::leading_zeros(4);
+        let sha1 = check_digest::<Sha1>("ckczppom", &condition);
+        let sha256 = check_digest::<Sha256>("ckczppom", &condition);
+        let md5 = check_digest::<Md5>("ckczppom", &condition);
+        assert_ne!(sha1, md5);
+        assert_ne!(sha256, md5);
+    }
 }
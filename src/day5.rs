@@ -1,14 +1,19 @@
 const FORBIDDEN: [[u8; 2]; 4] = [[97, 98], [99, 100], [112, 113], [120, 121]];
-const VOWELS: [u8; 5] = [b'a', b'e', b'i', b'o', b'u'];
+const VOWELS: [u8; 5] = *b"aeiou";
 
+use crate::compat::array_windows;
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashMap;
+use std::fmt;
+use std::str::FromStr;
 
 #[allow(dead_code)]
 #[inline(always)]
 fn is_nice_string(s: &str) -> bool {
     let mut vowel_count = 0;
     let mut double_letter: bool = false;
-    for window in s.as_bytes().array_windows::<2>() {
+    for window in array_windows::<_, 2>(s.as_bytes()) {
         if vowel_count < 3 && VOWELS.contains(&window[0]) {
             vowel_count += 1;
         }
@@ -20,61 +25,291 @@ fn is_nice_string(s: &str) -> bool {
         }
     }
     // Check if last byte is a vowel
-    if VOWELS.contains(&s.bytes().last().unwrap()) {
+    if s.bytes().last().is_some_and(|c| VOWELS.contains(&c)) {
         vowel_count += 1;
     }
     vowel_count > 2 && double_letter
 }
 
+/// Works on characters, so strings of any length and non-ASCII strings are handled.
 #[allow(dead_code)]
 #[inline(always)]
 fn is_nice_string2(s: &str) -> bool {
     let mut repeat_letters = false;
     let mut found_pairs = false;
-    // Record byte pairs and their index
-    let mut pairs: FxHashMap<&[u8; 2], usize> = FxHashMap::default();
+    // Record character pairs and the index they first start at
+    let mut pairs: FxHashMap<(char, char), usize> = FxHashMap::default();
+    let (mut before_prev, mut prev) = (None, None);
 
-    for window in s.as_bytes().array_windows::<3>() {
+    for (i, c) in s.chars().enumerate() {
         if !repeat_letters {
-            repeat_letters = window[0] == window[2];
-            if found_pairs {
-                break;
+            repeat_letters = before_prev == Some(c);
+        }
+        if let (false, Some(prev)) = (found_pairs, prev) {
+            let first = *pairs.entry((prev, c)).or_insert(i);
+            // Pairs starting one character apart overlap, e.g. in "aaa"
+            found_pairs = i >= first + 2;
+        }
+        if repeat_letters && found_pairs {
+            return true;
+        }
+        (before_prev, prev) = (prev, Some(c));
+    }
+    false
+}
+
+/// Where the features both classifiers look for appear in a string, as character indices.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Explanation {
+    /// Indices of every vowel.
+    pub vowels: Vec<usize>,
+    /// Index of the first letter of the first doubled letter.
+    pub double_letter: Option<usize>,
+    /// The first forbidden pair and its index.
+    pub forbidden: Option<([char; 2], usize)>,
+    /// Indices of the first pair found again without overlapping, e.g. (0, 3) for "xyaxy".
+    pub repeated_pair: Option<(usize, usize)>,
+    /// Index of the first `xyx` triple.
+    pub triple: Option<usize>,
+}
+
+impl Explanation {
+    pub fn new(s: &str) -> Self {
+        let mut explanation = Self::default();
+        // Index each pair first starts at
+        let mut pairs: FxHashMap<(char, char), usize> = FxHashMap::default();
+        let (mut before_prev, mut prev) = (None, None);
+
+        for (i, c) in s.chars().enumerate() {
+            if c.is_ascii() && VOWELS.contains(&(c as u8)) {
+                explanation.vowels.push(i);
+            }
+            if let Some(prev) = prev {
+                let start = i - 1;
+                if explanation.double_letter.is_none() && prev == c {
+                    explanation.double_letter = Some(start);
+                }
+                if explanation.forbidden.is_none()
+                    && FORBIDDEN
+                        .iter()
+                        .any(|&[a, b]| [a as char, b as char] == [prev, c])
+                {
+                    explanation.forbidden = Some(([prev, c], start));
+                }
+                if explanation.repeated_pair.is_none() {
+                    let first = *pairs.entry((prev, c)).or_insert(start);
+                    if start >= first + 2 {
+                        explanation.repeated_pair = Some((first, start));
+                    }
+                }
             }
+            if explanation.triple.is_none() && before_prev == Some(c) {
+                explanation.triple = Some(i - 2);
+            }
+            (before_prev, prev) = (prev, Some(c));
         }
+        explanation
+    }
+
+    /// Whether the string is nice by the rules of part 1.
+    pub fn is_nice(&self) -> bool {
+        self.vowels.len() >= 3 && self.double_letter.is_some() && self.forbidden.is_none()
     }
-    // Look for identical pairs
-    // SAFETY: str length (16) is divisible by chunk length (2)
-    for (i, pair) in unsafe { s.as_bytes().as_chunks_unchecked::<2>().iter().enumerate() } {
-        let i = i * 2;
-        if pairs.contains_key(pair) {
-            found_pairs = true;
-            break;
-        } else {
-            pairs.insert(pair, i);
+
+    /// Whether the string is nice by the rules of part 2.
+    pub fn is_nice2(&self) -> bool {
+        self.repeated_pair.is_some() && self.triple.is_some()
+    }
+}
+
+/// A rule nice strings follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// At least `count` of the characters are vowels.
+    MinVowels { count: usize, vowels: String },
+    /// None of the substrings appear.
+    Forbidden(Vec<String>),
+    /// Some character appears twice with exactly this many characters in between, e.g. 0 for
+    /// "xx" and 1 for "xyx".
+    RepeatWithGap(usize),
+    /// Some substring of this many characters appears twice without overlapping.
+    RepeatedNgram(usize),
+}
+
+impl Rule {
+    pub fn check(&self, s: &str) -> bool {
+        match self {
+            Rule::MinVowels { count, vowels } => {
+                s.chars().filter(|&c| vowels.contains(c)).count() >= *count
+            }
+            Rule::Forbidden(substrings) => !substrings.iter().any(|sub| s.contains(sub.as_str())),
+            Rule::RepeatWithGap(gap) => {
+                let chars: Vec<char> = s.chars().collect();
+                // Gaps too long to fit in any string never match
+                gap.checked_add(2).is_some_and(|len| {
+                    chars
+                        .windows(len)
+                        .any(|window| window[0] == window[len - 1])
+                })
+            }
+            Rule::RepeatedNgram(n) => {
+                let chars: Vec<char> = s.chars().collect();
+                // First index of each n-gram
+                let mut first_seen: FxHashMap<&[char], usize> = FxHashMap::default();
+                chars.windows(*n).enumerate().any(|(i, ngram)| {
+                    let first = *first_seen.entry(ngram).or_insert(i);
+                    i >= first + n
+                })
+            }
         }
     }
-    // View chunks offset +1, look for identical pairs and ensure the difference in their index is >1.
-    if !found_pairs {
-        // SAFETY: str length (14) is divisible by chunk length (2)
-        for (i, pair) in unsafe {
-            s.as_bytes()[1..15]
-                .as_chunks_unchecked::<2>()
-                .iter()
-                .enumerate()
-        } {
-            let i = i * 2 + 1;
-            if let Some(prev_i) = pairs.get(pair) {
-                // If pair was previously found and index difference is >1
-                found_pairs = (i as i32 - (*prev_i as i32)).abs() > 1;
-                if found_pairs {
-                    break;
-                }
-            } else {
-                pairs.insert(pair, i);
+}
+
+/// Written the way rule sets are parsed, e.g. "vowels 3 aeiou".
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::MinVowels { count, vowels } => write!(f, "vowels {} {}", count, vowels),
+            Rule::Forbidden(substrings) => write!(f, "forbid {}", substrings.join(" ")),
+            Rule::RepeatWithGap(gap) => write!(f, "repeat-gap {}", gap),
+            Rule::RepeatedNgram(n) => write!(f, "ngram {}", n),
+        }
+    }
+}
+
+/// Rules that all have to pass for a string to be nice.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn min_vowels(mut self, count: usize, vowels: &str) -> Self {
+        self.rules.push(Rule::MinVowels {
+            count,
+            vowels: vowels.to_string(),
+        });
+        self
+    }
+
+    pub fn forbid(mut self, substrings: &[&str]) -> Self {
+        self.rules.push(Rule::Forbidden(
+            substrings.iter().map(|sub| sub.to_string()).collect(),
+        ));
+        self
+    }
+
+    pub fn repeat_with_gap(mut self, gap: usize) -> Self {
+        self.rules.push(Rule::RepeatWithGap(gap));
+        self
+    }
+
+    pub fn repeated_ngram(mut self, n: usize) -> Self {
+        assert!(n > 0, "n-grams can't be empty");
+        self.rules.push(Rule::RepeatedNgram(n));
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The rules of part 1.
+    pub fn part1() -> Self {
+        Self::new()
+            .min_vowels(3, "aeiou")
+            .repeat_with_gap(0)
+            .forbid(&["ab", "cd", "pq", "xy"])
+    }
+
+    /// The rules of part 2.
+    pub fn part2() -> Self {
+        Self::new().repeated_ngram(2).repeat_with_gap(1)
+    }
+
+    /// Fails with the first rule the string breaks.
+    pub fn check(&self, s: &str) -> Result<(), &Rule> {
+        match self.rules.iter().find(|rule| !rule.check(s)) {
+            Some(rule) => Err(rule),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_nice(&self, s: &str) -> bool {
+        self.check(s).is_ok()
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = ParseError;
+
+    /// Parse one rule per line (or separated by `;`), e.g.
+    /// "vowels 3 aeiou; repeat-gap 0; forbid ab cd pq xy" or "ngram 2; repeat-gap 1".
+    fn from_str(rules: &str) -> Result<Self, Self::Err> {
+        let mut rule_set = Self::new();
+        for line in lines(rules) {
+            for rule in line.text.split(';') {
+                let mut tokens = rule.split_whitespace();
+                let Some(name) = tokens.next() else {
+                    continue;
+                };
+                let rule = match name {
+                    "vowels" => Rule::MinVowels {
+                        count: line.parse_next(&mut tokens, "a vowel count")?,
+                        vowels: line.expect(tokens.next(), "vowels")?.to_string(),
+                    },
+                    "forbid" => {
+                        let substrings: Vec<String> = tokens.by_ref().map(String::from).collect();
+                        if substrings.is_empty() {
+                            return Err(line.error("", "expected substrings to forbid"));
+                        }
+                        Rule::Forbidden(substrings)
+                    }
+                    "repeat-gap" => Rule::RepeatWithGap(line.parse_next(&mut tokens, "a gap")?),
+                    "ngram" => match line.parse_next(&mut tokens, "an n-gram length")? {
+                        0 => return Err(line.error(name, "n-grams can't be empty")),
+                        n => Rule::RepeatedNgram(n),
+                    },
+                    _ => return Err(line.error(name, "unknown rule")),
+                };
+                line.expect_end(&mut tokens)?;
+                rule_set.rules.push(rule);
             }
         }
+        Ok(rule_set)
+    }
+}
+
+pub struct Day5 {
+    strings: String,
+}
+
+impl Solution for Day5 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            strings: input.to_string(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.strings
+            .lines()
+            .filter(|line| is_nice_string(line))
+            .count()
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.strings
+            .lines()
+            .filter(|line| is_nice_string2(line))
+            .count()
+            .into()
     }
-    repeat_letters && found_pairs
 }
 
 #[cfg(test)]
@@ -104,6 +339,15 @@ mod solution {
         assert_eq!(count, 69);
     }
 
+    #[test]
+    fn empty_strings() {
+        assert!(!is_nice_string(""));
+        assert!(!is_nice_string("a"));
+        let day = Day5::parse("abc\n\naaa\n").unwrap();
+        assert_eq!(day.part1(), Answer::UInt(1));
+        assert_eq!(day.part2(), Answer::UInt(0));
+    }
+
     #[test]
     fn test_is_nice_string2_general() {
         // repeat letter (hvh) with identical pair (st) at even spacing
@@ -129,4 +373,126 @@ mod solution {
         let s: &str = "qjcaaahzxzaqkmpb";
         assert!(!is_nice_string2(s));
     }
+
+    /// Checks every pair of pairs and every triple.
+    fn is_nice_string2_naive(s: &str) -> bool {
+        let chars: Vec<char> = s.chars().collect();
+        let n = chars.len();
+        let found_pairs = (0..n.saturating_sub(1))
+            .any(|i| (i + 2..n.saturating_sub(1)).any(|j| chars[i..i + 2] == chars[j..j + 2]));
+        let repeat_letters = (0..n.saturating_sub(2)).any(|i| chars[i] == chars[i + 2]);
+        found_pairs && repeat_letters
+    }
+
+    #[test]
+    fn test_is_nice_string2_lengths() {
+        assert!(!is_nice_string2(""));
+        assert!(!is_nice_string2("aaa"));
+        assert!(is_nice_string2("aaaa"));
+        assert!(is_nice_string2("xyxy"));
+        assert!(is_nice_string2("qjhvhtzxzqqjkmpbqq"));
+        assert!(is_nice_string2("éééé"));
+        assert!(!is_nice_string2("🎄é🎄"));
+        assert!(is_nice_string2("🎄é🎄é"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn is_nice_string2_matches_naive(s in "[abcé🎄]{0,24}") {
+            proptest::prop_assert_eq!(is_nice_string2(&s), is_nice_string2_naive(&s));
+        }
+
+        #[test]
+        fn is_nice_string2_matches_naive_any(s in "\\PC{0,40}") {
+            proptest::prop_assert_eq!(is_nice_string2(&s), is_nice_string2_naive(&s));
+        }
+    }
+
+    #[test]
+    fn explain_strings() {
+        assert_eq!(
+            Explanation::new("ugknbfddgicrmopn"),
+            Explanation {
+                vowels: vec![0, 9, 13],
+                double_letter: Some(6),
+                ..Default::default()
+            }
+        );
+        let explanation = Explanation::new("haegwjzuvuyypxyu");
+        assert_eq!(explanation.forbidden, Some((['x', 'y'], 13)));
+        assert_eq!(explanation.double_letter, Some(10));
+        assert!(!explanation.is_nice());
+
+        let explanation = Explanation::new("qjhvhtzxzqqjkmpb");
+        assert_eq!(explanation.repeated_pair, Some((0, 10)));
+        assert_eq!(explanation.triple, Some(2));
+        assert!(explanation.is_nice2());
+        // Overlapping pairs don't count
+        assert_eq!(Explanation::new("aaa").repeated_pair, None);
+        assert_eq!(Explanation::new("éaéa").repeated_pair, Some((0, 2)));
+
+        let strings = get_input("strings").unwrap();
+        for line in strings.lines() {
+            let explanation = Explanation::new(line);
+            assert_eq!(explanation.is_nice(), is_nice_string(line), "{}", line);
+            assert_eq!(explanation.is_nice2(), is_nice_string2(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn rule_sets_match_classifiers() {
+        let strings = get_input("strings").unwrap();
+        let (part1, part2) = (RuleSet::part1(), RuleSet::part2());
+        for line in strings.lines() {
+            assert_eq!(part1.is_nice(line), is_nice_string(line), "{}", line);
+            assert_eq!(part2.is_nice(line), is_nice_string2(line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn rule_set_reports_failed_rule() {
+        let part1 = RuleSet::part1();
+        assert_eq!(part1.check("ugknbfddgicrmopn"), Ok(()));
+        assert_eq!(
+            part1.check("dvszwmarrgswjxmb").unwrap_err().to_string(),
+            "vowels 3 aeiou"
+        );
+        assert_eq!(
+            part1.check("jchzalrnumimnmhp").unwrap_err(),
+            &Rule::RepeatWithGap(0)
+        );
+        assert_eq!(
+            part1.check("haegwjzuvuyypxyu").unwrap_err().to_string(),
+            "forbid ab cd pq xy"
+        );
+
+        // Repeated n-grams must not overlap
+        let triples = RuleSet::new().repeated_ngram(3);
+        assert!(triples.is_nice("abcxabc"));
+        assert!(!triples.is_nice("aaaa"));
+        assert!(triples.is_nice("aaaaaa"));
+    }
+
+    #[test]
+    fn parse_rule_set() {
+        let rules: RuleSet = "vowels 3 aeiou; repeat-gap 0\nforbid ab cd pq xy"
+            .parse()
+            .unwrap();
+        assert_eq!(rules, RuleSet::part1());
+        assert_eq!("ngram 2; repeat-gap 1".parse(), Ok(RuleSet::part2()));
+
+        let e = "ngram 2\nrepeat-gap x".parse::<RuleSet>().unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 12, "x"));
+        // Gaps too long for any string parse, but never match
+        let rules: RuleSet = "repeat-gap 18446744073709551615".parse().unwrap();
+        assert_eq!(rules, RuleSet::new().repeat_with_gap(usize::MAX));
+        assert!(!rules.is_nice("aaaa"));
+        assert!(!rules.is_nice(""));
+
+        let e = "vowels 3 aeiou; palindrome".parse::<RuleSet>().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 17: unknown rule, found `palindrome`"
+        );
+    }
 }
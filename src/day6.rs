@@ -1,140 +1,224 @@
+use crate::parse::{lines, parse_lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
 use std::str::FromStr;
 
-#[allow(dead_code)]
-#[derive(Debug)]
-struct Range((usize, usize), (usize, usize));
+/// The size of the puzzle's grid.
+const SIZE: usize = 1000;
 
-#[allow(dead_code)]
-#[derive(Debug)]
-enum Instruction {
+/// Inclusive (row, column) corners, the first one being the top left once normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range(pub (usize, usize), pub (usize, usize));
+
+impl Range {
+    /// The range between two opposite corners, in any order.
+    pub fn new(corner1: (usize, usize), corner2: (usize, usize)) -> Self {
+        Range(corner1, corner2).normalized()
+    }
+
+    pub fn normalized(self) -> Self {
+        let Range((i0, j0), (i1, j1)) = self;
+        Range((i0.min(i1), j0.min(j1)), (i0.max(i1), j0.max(j1)))
+    }
+
+    /// The normalized range, if it fits in a grid of this many rows and columns.
+    pub fn within(self, height: usize, width: usize) -> Result<Self, OutOfBounds> {
+        let range = self.normalized();
+        if range.1 .0 < height && range.1 .1 < width {
+            Ok(range)
+        } else {
+            Err(OutOfBounds {
+                range,
+                height,
+                width,
+            })
+        }
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},{} through {},{}",
+            self.0 .0, self.0 .1, self.1 .0, self.1 .1
+        )
+    }
+}
+
+/// An instruction's range doesn't fit in the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub range: Range,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is outside of the {}x{} grid",
+            self.range, self.height, self.width
+        )
+    }
+}
+
+impl Error for OutOfBounds {}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
     TurnOn(Range),
     TurnOff(Range),
     Toggle(Range),
 }
 
-trait Light {}
+impl Instruction {
+    pub fn range(&self) -> Range {
+        match *self {
+            Instruction::TurnOn(range)
+            | Instruction::TurnOff(range)
+            | Instruction::Toggle(range) => range,
+        }
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for bool {}
+    impl Sealed for i32 {}
+}
+
+/// Lights start off, which is the default value. The trait is sealed because `LightGrid` relies
+/// on all zero bits being a valid light that is off, which holds for `bool` and `i32`.
+pub trait Light: Default + sealed::Sealed {}
 impl Light for bool {}
 impl Light for i32 {}
 
-#[allow(dead_code)]
-trait ExecuteInstruction {
+pub trait ExecuteInstruction {
+    /// Fails without changing any light if the range doesn't fit in the grid.
     #[inline(always)]
-    fn execute(&mut self, instruction: Instruction) {
+    fn execute(&mut self, instruction: Instruction) -> Result<(), OutOfBounds> {
+        let (height, width) = self.dimensions();
+        let range = instruction.range().within(height, width)?;
         match instruction {
-            Instruction::Toggle(range) => {
+            Instruction::Toggle(_) => {
                 self.toggle(range);
             }
-            Instruction::TurnOn(range) => {
+            Instruction::TurnOn(_) => {
                 self.turn_on(range);
             }
-            Instruction::TurnOff(range) => {
+            Instruction::TurnOff(_) => {
                 self.turn_off(range);
             }
         }
+        Ok(())
     }
 
+    /// The number of rows and columns.
+    fn dimensions(&self) -> (usize, usize);
+
+    // The range must fit in the grid
     fn turn_on(&mut self, range: Range);
     fn turn_off(&mut self, range: Range);
     fn toggle(&mut self, range: Range);
-    fn aggregate(&self) -> i32;
+    fn aggregate(&self) -> i64;
 }
 
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = ParseError;
 
+    /// Parse an instruction of the form "turn on 0,0 through 999,999".
     #[inline(always)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = Line::single(s);
         let mut tokens = s.split(' ');
-        let instruction_type; // 1 = Toggle, 2 = TurnOn, 3 = TurnOff
 
-        // Match first token
-        match tokens.next().unwrap() {
-            "toggle" => {
-                instruction_type = 1;
-            }
-            "turn" => {
-                // Match second token
-                match tokens.next().unwrap() {
-                    "on" => {
-                        instruction_type = 2;
-                    }
-                    "off" => {
-                        instruction_type = 3;
-                    }
-                    // should be unreachable
-                    _ => {
-                        instruction_type = -1;
-                    }
-                }
-            }
-            // should be unreachable
-            _ => {
-                instruction_type = -1;
-            }
-        }
-        // Map range string of the form "123,123" to iterator of usize
-        let mut corner1 = tokens
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|s| s.parse::<usize>().unwrap());
-        tokens.next(); // Throw away "through"
-        let mut corner2 = tokens
-            .next()
-            .unwrap()
-            .split(',')
-            .map(|s| s.parse::<usize>().unwrap());
-        match instruction_type {
-            1 => Ok(Instruction::Toggle(Range(
-                (corner1.next().unwrap(), corner1.next().unwrap()),
-                (corner2.next().unwrap(), corner2.next().unwrap()),
-            ))),
-            2 => Ok(Instruction::TurnOn(Range(
-                (corner1.next().unwrap(), corner1.next().unwrap()),
-                (corner2.next().unwrap(), corner2.next().unwrap()),
-            ))),
-            3 => Ok(Instruction::TurnOff(Range(
-                (corner1.next().unwrap(), corner1.next().unwrap()),
-                (corner2.next().unwrap(), corner2.next().unwrap()),
-            ))),
-            // Technically unreachable
-            _ => Err(format!(
-                "The following instruction could not be parsed: `{}`",
-                s
-            )),
-        }
-    }
-}
-
-struct LightGrid<T>
+        // Match first token, and second token for "turn"
+        let token = line.expect(tokens.next(), "`toggle` or `turn`")?;
+        let instruction: fn(Range) -> Instruction = match token {
+            "toggle" => Instruction::Toggle,
+            "turn" => match line.expect(tokens.next(), "`on` or `off`")? {
+                "on" => Instruction::TurnOn,
+                "off" => Instruction::TurnOff,
+                token => return Err(line.error(token, "expected `on` or `off`")),
+            },
+            _ => return Err(line.error(token, "expected `toggle` or `turn`")),
+        };
+        // Map corner string of the form "123,123" to (usize, usize)
+        let parse_corner = |token: Option<&str>| -> Result<(usize, usize), ParseError> {
+            let token = line.expect(token, "a corner")?;
+            let mut coords = token.split(',');
+            let corner = (
+                line.parse_next(&mut coords, "a coordinate")?,
+                line.parse_next(&mut coords, "a coordinate")?,
+            );
+            line.expect_end(&mut coords)?;
+            Ok(corner)
+        };
+        let corner1 = parse_corner(tokens.next())?;
+        line.expect_literal(tokens.next(), "through")?;
+        let corner2 = parse_corner(tokens.next())?;
+        line.expect_end(&mut tokens)?;
+        Ok(instruction(Range::new(corner1, corner2)))
+    }
+}
+
+/// A grid of `HEIGHT` rows of `WIDTH` lights, one per element, all off to begin with.
+pub struct LightGrid<T, const HEIGHT: usize = SIZE, const WIDTH: usize = SIZE>
 where
     T: Light,
 {
-    grid: Box<[T; 1000000]>,
+    grid: Box<[T]>,
 }
 
-impl<T> LightGrid<T>
+impl<T, const HEIGHT: usize, const WIDTH: usize> LightGrid<T, HEIGHT, WIDTH>
 where
     T: Light + Copy,
 {
-    #[allow(dead_code)]
-    fn new_zeroed() -> LightGrid<T> {
+    #[cfg(feature = "nightly")]
+    pub fn new() -> Self {
+        LightGrid {
+            // SAFETY: `Light` is sealed, and only implemented for types where all zero bits are a
+            // valid value.
+            grid: unsafe { Box::new_zeroed_slice(HEIGHT * WIDTH).assume_init() },
+        }
+    }
+
+    #[cfg(not(feature = "nightly"))]
+    pub fn new() -> Self {
         LightGrid {
-            // SAFETY: safe for Light
-            grid: unsafe { Box::new_zeroed().assume_init() },
+            grid: vec![T::default(); HEIGHT * WIDTH].into_boxed_slice(),
         }
     }
 
     fn mutate_each(&mut self, range: Range, op: impl Fn(T) -> T) {
-        for i in range.0 .0..=range.1 .0 {
-            for j in range.0 .1..=range.1 .1 {
-                self.grid[i * 1000 + j] = op(self.grid[i * 1000 + j]);
+        let Range((i0, j0), (i1, j1)) = range.normalized();
+        for row in self.grid.chunks_exact_mut(WIDTH).take(i1 + 1).skip(i0) {
+            for light in &mut row[j0..=j1] {
+                *light = op(*light);
             }
         }
     }
 }
 
-impl ExecuteInstruction for LightGrid<bool> {
+impl<T, const HEIGHT: usize, const WIDTH: usize> Default for LightGrid<T, HEIGHT, WIDTH>
+where
+    T: Light + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const HEIGHT: usize, const WIDTH: usize> ExecuteInstruction
+    for LightGrid<bool, HEIGHT, WIDTH>
+{
+    fn dimensions(&self) -> (usize, usize) {
+        (HEIGHT, WIDTH)
+    }
+
     fn toggle(&mut self, range: Range) {
         self.mutate_each(range, |light| !light)
     }
@@ -147,14 +231,18 @@ impl ExecuteInstruction for LightGrid<bool> {
         self.mutate_each(range, |_| false)
     }
 
-    fn aggregate(&self) -> i32 {
+    fn aggregate(&self) -> i64 {
         self.grid
             .iter()
             .fold(0, |count, light| if *light { count + 1 } else { count })
     }
 }
 
-impl ExecuteInstruction for LightGrid<i32> {
+impl<const HEIGHT: usize, const WIDTH: usize> ExecuteInstruction for LightGrid<i32, HEIGHT, WIDTH> {
+    fn dimensions(&self) -> (usize, usize) {
+        (HEIGHT, WIDTH)
+    }
+
     fn toggle(&mut self, range: Range) {
         self.mutate_each(range, |light| light + 2)
     }
@@ -167,8 +255,193 @@ impl ExecuteInstruction for LightGrid<i32> {
         self.mutate_each(range, |light| if light > 0 { light - 1 } else { 0 });
     }
 
-    fn aggregate(&self) -> i32 {
-        self.grid.iter().sum()
+    fn aggregate(&self) -> i64 {
+        self.grid.iter().map(|&light| light as i64).sum()
+    }
+}
+
+/// A grid cut into bands of rows and columns along the edges of the instructions' ranges, with
+/// one light per cell standing for every light in it. The work per instruction depends on how
+/// many instructions came before, not on the area, so grids can be huge.
+pub struct CompressedGrid<T>
+where
+    T: Light,
+{
+    /// Sorted bounds of the bands of rows, from 0 to the height. Band `k` is the rows
+    /// `rows[k]..rows[k + 1]`.
+    rows: Vec<usize>,
+    /// Sorted bounds of the bands of columns, from 0 to the width.
+    columns: Vec<usize>,
+    /// Row-major, one per pair of bands.
+    cells: Vec<T>,
+}
+
+/// Add a bound at `at`, returning the band that was split in two, if any.
+fn split_band(bounds: &mut Vec<usize>, at: usize) -> Option<usize> {
+    match bounds.binary_search(&at) {
+        Ok(_) => None,
+        Err(k) => {
+            assert!(k < bounds.len(), "{} is outside of the grid", at);
+            bounds.insert(k, at);
+            Some(k - 1)
+        }
+    }
+}
+
+impl<T> CompressedGrid<T>
+where
+    T: Light + Copy,
+{
+    pub fn new(height: usize, width: usize) -> Self {
+        Self {
+            rows: vec![0, height],
+            columns: vec![0, width],
+            cells: vec![T::default()],
+        }
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (
+            self.rows[self.rows.len() - 1],
+            self.columns[self.columns.len() - 1],
+        )
+    }
+
+    fn width(&self) -> usize {
+        self.columns.len() - 1
+    }
+
+    fn split_rows(&mut self, at: usize) {
+        if let Some(band) = split_band(&mut self.rows, at) {
+            let width = self.width();
+            let row = band * width;
+            let copy = self.cells[row..row + width].to_vec();
+            self.cells.splice(row + width..row + width, copy);
+        }
+    }
+
+    fn split_columns(&mut self, at: usize) {
+        let width = self.width();
+        if let Some(band) = split_band(&mut self.columns, at) {
+            let mut cells = Vec::with_capacity(self.cells.len() / width * (width + 1));
+            for row in self.cells.chunks(width) {
+                cells.extend_from_slice(&row[..=band]);
+                cells.extend_from_slice(&row[band..]);
+            }
+            self.cells = cells;
+        }
+    }
+
+    fn mutate_each(&mut self, range: Range, op: impl Fn(T) -> T) {
+        let Range((i0, j0), (i1, j1)) = range.normalized();
+        self.split_rows(i0);
+        self.split_rows(i1 + 1);
+        self.split_columns(j0);
+        self.split_columns(j1 + 1);
+
+        let band = |bounds: &[usize], at| bounds.binary_search(&at).unwrap();
+        let width = self.width();
+        for i in band(&self.rows, i0)..band(&self.rows, i1 + 1) {
+            for j in band(&self.columns, j0)..band(&self.columns, j1 + 1) {
+                self.cells[i * width + j] = op(self.cells[i * width + j]);
+            }
+        }
+    }
+
+    /// Sum of `value` over every light.
+    fn sum(&self, value: impl Fn(T) -> i64) -> i64 {
+        let width = self.width();
+        let mut sum = 0;
+        for (i, rows) in self.rows.windows(2).enumerate() {
+            for (j, columns) in self.columns.windows(2).enumerate() {
+                let area = (rows[1] - rows[0]) * (columns[1] - columns[0]);
+                sum += value(self.cells[i * width + j]) * area as i64;
+            }
+        }
+        sum
+    }
+}
+
+impl ExecuteInstruction for CompressedGrid<bool> {
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions()
+    }
+
+    fn toggle(&mut self, range: Range) {
+        self.mutate_each(range, |light| !light)
+    }
+
+    fn turn_on(&mut self, range: Range) {
+        self.mutate_each(range, |_| true)
+    }
+
+    fn turn_off(&mut self, range: Range) {
+        self.mutate_each(range, |_| false)
+    }
+
+    fn aggregate(&self) -> i64 {
+        self.sum(|light| light as i64)
+    }
+}
+
+impl ExecuteInstruction for CompressedGrid<i32> {
+    fn dimensions(&self) -> (usize, usize) {
+        self.dimensions()
+    }
+
+    fn toggle(&mut self, range: Range) {
+        self.mutate_each(range, |light| light + 2)
+    }
+
+    fn turn_on(&mut self, range: Range) {
+        self.mutate_each(range, |light| light + 1)
+    }
+
+    fn turn_off(&mut self, range: Range) {
+        self.mutate_each(range, |light| if light > 0 { light - 1 } else { 0 });
+    }
+
+    fn aggregate(&self) -> i64 {
+        self.sum(|light| light as i64)
+    }
+}
+
+pub struct Day6 {
+    instructions: Vec<Instruction>,
+}
+
+impl Day6 {
+    fn execute_instructions<T>(&self) -> i64
+    where
+        T: Light + Copy,
+        LightGrid<T>: ExecuteInstruction,
+    {
+        let mut grid: LightGrid<T> = LightGrid::new();
+        for instruction in self.instructions.iter() {
+            grid.execute(*instruction)
+                .expect("instructions are checked when parsed");
+        }
+        grid.aggregate()
+    }
+}
+
+impl Solution for Day6 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let instructions: Vec<Instruction> = parse_lines(input)?;
+        for (line, instruction) in lines(input).zip(&instructions) {
+            if let Err(e) = instruction.range().within(SIZE, SIZE) {
+                return Err(line.error(line.text, e.to_string()));
+            }
+        }
+        Ok(Self { instructions })
+    }
+
+    fn part1(&self) -> Answer {
+        self.execute_instructions::<bool>().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.execute_instructions::<i32>().into()
     }
 }
 
@@ -179,28 +452,124 @@ mod solution {
 
     #[test]
     fn get_ideal_lighting_configuration1() {
-        let mut grid: LightGrid<bool> = LightGrid::new_zeroed();
+        let mut grid: LightGrid<bool> = LightGrid::new();
         for line in get_input("instructions").unwrap().lines() {
             let instruction = line.parse::<Instruction>().unwrap();
-            grid.execute(instruction);
+            grid.execute(instruction).unwrap();
         }
         assert_eq!(grid.aggregate(), 543903);
     }
 
     #[test]
     fn get_ideal_lighting_configuration2() {
-        let mut grid: LightGrid<i32> = LightGrid::new_zeroed();
+        let mut grid: LightGrid<i32> = LightGrid::new();
         for line in get_input("instructions").unwrap().lines() {
             let instruction = line.parse::<Instruction>().unwrap();
-            grid.execute(instruction);
+            grid.execute(instruction).unwrap();
         }
         assert_eq!(grid.aggregate(), 14687245);
     }
 
+    #[test]
+    fn test_parse_instruction_errors() {
+        let e = "turn of 0,0 through 1,1"
+            .parse::<Instruction>()
+            .unwrap_err();
+        assert_eq!((e.column, e.token.as_str()), (6, "of"));
+
+        let e =
+            parse_lines::<Instruction>("toggle 0,0 through 1,1\ntoggle 0,0 thru 1,1").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 12: expected `through`, found `thru`"
+        );
+
+        let e = "toggle 0,0 through 1,x".parse::<Instruction>().unwrap_err();
+        assert_eq!((e.column, e.token.as_str()), (22, "x"));
+    }
+
     #[test]
     fn test_lightgrid() {
-        let mut grid: LightGrid<bool> = LightGrid::new_zeroed();
+        let mut grid: LightGrid<bool> = LightGrid::new();
         grid.turn_on(Range((499, 499), (500, 500)));
         assert_eq!(grid.aggregate(), 4);
     }
+
+    #[test]
+    fn compressed_grid_matches_light_grid() {
+        let instructions: Vec<Instruction> =
+            parse_lines(&get_input("instructions").unwrap()).unwrap();
+        let mut grid: CompressedGrid<bool> = CompressedGrid::new(1000, 1000);
+        let mut brightness: CompressedGrid<i32> = CompressedGrid::new(1000, 1000);
+        for &instruction in &instructions {
+            grid.execute(instruction).unwrap();
+            brightness.execute(instruction).unwrap();
+        }
+        assert_eq!(grid.aggregate(), 543903);
+        assert_eq!(brightness.aggregate(), 14687245);
+    }
+
+    #[test]
+    fn test_compressed_grid() {
+        let mut grid: CompressedGrid<bool> = CompressedGrid::new(1_000_000, 1_000_000);
+        grid.turn_on(Range((0, 0), (999_999, 999_999)));
+        assert_eq!(grid.aggregate(), 1_000_000_000_000);
+        grid.toggle(Range((0, 0), (999_999, 0)));
+        grid.turn_off(Range((499_999, 499_999), (500_000, 500_000)));
+        assert_eq!(grid.aggregate(), 1_000_000_000_000 - 1_000_000 - 4);
+        assert_eq!(grid.cells.len(), 4 * 3);
+
+        let mut grid: CompressedGrid<i32> = CompressedGrid::new(1_000_000, 1_000_000);
+        grid.toggle(Range((10, 20), (10, 999_999)));
+        grid.turn_off(Range((0, 0), (10, 29)));
+        assert_eq!(grid.aggregate(), 2 * (1_000_000 - 20) - 10);
+    }
+
+    #[test]
+    fn test_reversed_corners() {
+        let instruction: Instruction = "turn on 5,1 through 2,3".parse().unwrap();
+        assert!(matches!(
+            instruction,
+            Instruction::TurnOn(Range((2, 1), (5, 3)))
+        ));
+
+        let mut grid: LightGrid<bool> = LightGrid::new();
+        grid.turn_on(Range((500, 500), (499, 499)));
+        assert_eq!(grid.aggregate(), 4);
+        let mut grid: CompressedGrid<i32> = CompressedGrid::new(10, 10);
+        grid.toggle(Range((9, 0), (0, 9)));
+        assert_eq!(grid.aggregate(), 200);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut grid: LightGrid<i32, 3, 4> = LightGrid::new();
+        grid.execute("turn on 0,0 through 2,3".parse().unwrap())
+            .unwrap();
+        assert_eq!(grid.aggregate(), 12);
+        let e = grid
+            .execute("toggle 0,0 through 3,3".parse().unwrap())
+            .unwrap_err();
+        assert_eq!(e.to_string(), "0,0 through 3,3 is outside of the 3x4 grid");
+        assert!(grid
+            .execute("toggle 0,4 through 0,0".parse().unwrap())
+            .is_err());
+        assert_eq!(grid.aggregate(), 12);
+
+        let mut grid: CompressedGrid<bool> = CompressedGrid::new(1_000_000, 1_000_000);
+        let e = grid
+            .execute("turn on 0,0 through 1000000,0".parse().unwrap())
+            .unwrap_err();
+        assert_eq!((e.height, e.width), (1_000_000, 1_000_000));
+        assert_eq!(grid.aggregate(), 0);
+
+        let e = Day6::parse("toggle 0,0 through 1,1\nturn off 999,0 through 1000,999")
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 1: 999,0 through 1000,999 is outside of the 1000x1000 grid, \
+             found `turn off 999,0 through 1000,999`"
+        );
+    }
 }
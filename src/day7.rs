@@ -1,20 +1,107 @@
 //! The solution here basically involves putting every wire on a queue sorted by how many signals it's missing.
 //! We then take all the wires with no missing signals off the queue, propagate their signals, re-sort the queue and repeat.
-use rustc_hash::FxHashMap;
+use crate::parse::{lines, Line, ParseError};
+use crate::solution::{Answer, Solution};
+use rustc_hash::{FxHashMap, FxHashSet};
 
 #[allow(dead_code)]
 #[derive(Debug)]
-enum WireSource<'a> {
-    GateAndWire(&'a str, &'a str),
-    GateAndLiteral(u16, &'a str),
-    GateOr(&'a str, &'a str),
-    GateNot(&'a str),
-    GateRshift(&'a str, i32),
-    GateLshift(&'a str, i32),
-    Wire(&'a str),
+enum WireSource<W> {
+    GateAndWire(W, W),
+    GateAndLiteral(u16, W),
+    GateOr(W, W),
+    GateNot(W),
+    GateRshift(W, i32),
+    GateLshift(W, i32),
+    Wire(W),
     Signal(u16),
 }
 
+impl<W> WireSource<W> {
+    /// Convert the wire identifiers, e.g. between borrowed and owned names.
+    fn map<'b, V>(&'b self, f: impl Fn(&'b W) -> V) -> WireSource<V> {
+        match self {
+            WireSource::GateAndWire(wire1, wire2) => WireSource::GateAndWire(f(wire1), f(wire2)),
+            WireSource::GateAndLiteral(signal, wire) => {
+                WireSource::GateAndLiteral(*signal, f(wire))
+            }
+            WireSource::GateOr(wire1, wire2) => WireSource::GateOr(f(wire1), f(wire2)),
+            WireSource::GateNot(wire) => WireSource::GateNot(f(wire)),
+            WireSource::GateRshift(wire, shift) => WireSource::GateRshift(f(wire), *shift),
+            WireSource::GateLshift(wire, shift) => WireSource::GateLshift(f(wire), *shift),
+            WireSource::Wire(wire) => WireSource::Wire(f(wire)),
+            WireSource::Signal(signal) => WireSource::Signal(*signal),
+        }
+    }
+
+    /// The wires feeding this one.
+    fn source_wires(&self) -> Vec<&W> {
+        match self {
+            WireSource::GateAndWire(wire1, wire2) | WireSource::GateOr(wire1, wire2) => {
+                vec![wire1, wire2]
+            }
+            WireSource::GateAndLiteral(_, wire)
+            | WireSource::GateNot(wire)
+            | WireSource::GateRshift(wire, _)
+            | WireSource::GateLshift(wire, _)
+            | WireSource::Wire(wire) => vec![wire],
+            WireSource::Signal(_) => vec![],
+        }
+    }
+}
+
+/// Parse a line of the form "x AND y -> z" to (source, output wire).
+fn parse_wire(line: Line<'_>) -> Result<(WireSource<&str>, &str), ParseError> {
+    // line_split: input wire -> output wire
+    let (input, output_wire) = line
+        .text
+        .split_once(" -> ")
+        .ok_or_else(|| line.error("", "expected `->`"))?;
+    // input_split: [NOT] (wire_ident | literal) (OR | AND | RSHIFT | LSHIFT) (wire_ident | literal)
+    let mut input_split = input.split(' ').peekable();
+    let token1 = line.expect(input_split.next(), "a wire or signal")?;
+
+    // Condition branch off token1
+    if token1 == "NOT" {
+        let source = WireSource::GateNot(line.expect(input_split.next(), "a wire")?);
+        line.expect_end(&mut input_split)?;
+        return Ok((source, output_wire));
+    } else if let Ok(signal) = token1.parse::<u16>() {
+        // If token1 is a literal
+        if let Some(token2) = input_split.peek() {
+            if *token2 == "AND" {
+                input_split.next(); // Consume "AND"
+                let source = WireSource::GateAndLiteral(
+                    signal,
+                    line.expect(input_split.next(), "a wire")?, // Second source wire
+                );
+                line.expect_end(&mut input_split)?;
+                return Ok((source, output_wire));
+            }
+        } else {
+            // Wire source type is Signal
+            return Ok((WireSource::Signal(signal), output_wire));
+        }
+    }
+    // Token1 is an input wire identifier
+    let Some(token2) = input_split.next() else {
+        return Ok((WireSource::Wire(token1), output_wire));
+    };
+    let source = match token2 {
+        "RSHIFT" => {
+            WireSource::GateRshift(token1, line.parse_next(&mut input_split, "a shift amount")?)
+        }
+        "LSHIFT" => {
+            WireSource::GateLshift(token1, line.parse_next(&mut input_split, "a shift amount")?)
+        }
+        "AND" => WireSource::GateAndWire(token1, line.expect(input_split.next(), "a wire")?),
+        "OR" => WireSource::GateOr(token1, line.expect(input_split.next(), "a wire")?),
+        _ => return Err(line.error(token2, "expected a gate")),
+    };
+    line.expect_end(&mut input_split)?;
+    Ok((source, output_wire))
+}
+
 #[derive(Debug)]
 struct WireState {
     signal: Option<u16>,
@@ -25,7 +112,7 @@ struct WireState {
 #[allow(dead_code)]
 struct Circuit<'a> {
     /// The source wires for each wire.
-    wires_upstream: FxHashMap<&'a str, WireSource<'a>>,
+    wires_upstream: FxHashMap<&'a str, WireSource<&'a str>>,
     /// The downstream wires for each wire.
     wires_downstreams: FxHashMap<&'a str, Vec<&'a str>>,
     /// Mutable state data for each wire.
@@ -64,7 +151,7 @@ impl<'a> Circuit<'a> {
         self.sorted_up_to = front;
     }
 
-    fn add_wire(&mut self, source: WireSource<'a>, wire: &'a str) {
+    fn add_wire(&mut self, source: WireSource<&'a str>, wire: &'a str) {
         // Populate wire downstreams and get initial wire state
         let wire_state = match source {
             WireSource::GateAndWire(source_wire1, source_wire2)
@@ -107,86 +194,17 @@ impl<'a> Circuit<'a> {
         self.queue.push(wire);
     }
 
-    fn connect_wires(&mut self, wires: &'a str) {
-        for line in wires.lines() {
-            // line_split: input wire -> output wire
-            let mut line_split = line.split("->");
-            // input_split: [NOT] (wire_ident | literal) (OR | AND | RSHIFT | LSHIFT) (wire_ident | literal)
-            let mut input_split = line_split
-                .next()
-                .unwrap()
-                .strip_suffix(' ')
-                .unwrap()
-                .split(' ')
-                .peekable();
-            let output_wire = line_split.next().unwrap().strip_prefix(' ').unwrap();
-            let token1 = input_split.next().unwrap();
-
-            // Condition branch off token1
-            if token1 == "NOT" {
-                self.add_wire(
-                    WireSource::GateNot(input_split.next().unwrap()),
-                    output_wire,
-                );
-                continue;
-            } else if let Ok(signal) = token1.parse::<u16>() {
-                // If token1 is a literal
-                if let Some(token2) = input_split.peek() {
-                    if *token2 == "AND" {
-                        input_split.next(); // Consume "AND"
-                        self.add_wire(
-                            WireSource::GateAndLiteral(
-                                signal,
-                                input_split.next().unwrap(), // Second source wire
-                            ),
-                            output_wire,
-                        );
-                        continue;
-                    }
-                } else {
-                    // Wire source type is Signal
-                    self.add_wire(WireSource::Signal(signal), output_wire);
-                    continue;
-                }
-            }
-            // Token1 is an input wire identifier
-            if let Some(token2) = input_split.next() {
-                match token2 {
-                    "RSHIFT" => {
-                        self.add_wire(
-                            WireSource::GateRshift(
-                                token1,
-                                input_split.next().unwrap().parse::<i32>().unwrap(),
-                            ),
-                            output_wire,
-                        );
-                    }
-                    "LSHIFT" => {
-                        self.add_wire(
-                            WireSource::GateLshift(
-                                token1,
-                                input_split.next().unwrap().parse::<i32>().unwrap(),
-                            ),
-                            output_wire,
-                        );
-                    }
-                    "AND" => {
-                        self.add_wire(
-                            WireSource::GateAndWire(token1, input_split.next().unwrap()),
-                            output_wire,
-                        );
-                    }
-                    "OR" => {
-                        self.add_wire(
-                            WireSource::GateOr(token1, input_split.next().unwrap()),
-                            output_wire,
-                        );
-                    }
-                    _ => panic!("Invalid line: {}", line),
-                }
-            } else {
-                self.add_wire(WireSource::Wire(token1), output_wire);
-            }
+    fn connect_wires(&mut self, wires: &'a str) -> Result<(), ParseError> {
+        let wires = lines(wires)
+            .map(parse_wire)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.connect(wires);
+        Ok(())
+    }
+
+    fn connect(&mut self, wires: impl IntoIterator<Item = (WireSource<&'a str>, &'a str)>) {
+        for (source, wire) in wires {
+            self.add_wire(source, wire);
         }
         self.queue.sort_unstable_by(|identifier1, identifier2| {
             let wire1 = self.wires_state.get(identifier1).unwrap();
@@ -279,6 +297,69 @@ impl<'a> Circuit<'a> {
     }
 }
 
+fn get_signal_a<'a>(wires: impl IntoIterator<Item = (WireSource<&'a str>, &'a str)>) -> u16 {
+    let mut circuit = Circuit::new();
+    circuit.connect(wires);
+    circuit.propagate_signals();
+    circuit.get_signal("a")
+}
+
+pub struct Day7 {
+    /// Each wire's source and the wire it feeds.
+    wires: Vec<(WireSource<String>, String)>,
+}
+
+impl Day7 {
+    fn wires(&self) -> impl Iterator<Item = (WireSource<&str>, &str)> {
+        self.wires
+            .iter()
+            .map(|(source, wire)| (source.map(String::as_str), wire.as_str()))
+    }
+}
+
+impl Solution for Day7 {
+    /// Every wire that feeds another has to be connected, and so does wire a.
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let wires = lines(input)
+            .map(|line| Ok((line, parse_wire(line)?)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let outputs: FxHashSet<&str> = wires.iter().map(|(_, (_, wire))| *wire).collect();
+        for (line, (source, _)) in &wires {
+            if let Some(wire) = source
+                .source_wires()
+                .into_iter()
+                .find(|w| !outputs.contains(*w))
+            {
+                return Err(line.error(wire, "expected a connected wire"));
+            }
+        }
+        if !outputs.contains("a") {
+            let last_line = input.lines().count();
+            return Err(ParseError::new(last_line + 1, "", "", "expected wire `a`"));
+        }
+        Ok(Self {
+            wires: wires
+                .iter()
+                .map(|(_, (source, wire))| (source.map(|w| w.to_string()), wire.to_string()))
+                .collect(),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        get_signal_a(self.wires()).into()
+    }
+
+    /// Override wire b with the signal of wire a and propagate again.
+    fn part2(&self) -> Answer {
+        let signal_a = get_signal_a(self.wires());
+        let overridden = self
+            .wires()
+            .filter(|(_, wire)| *wire != "b")
+            .chain([(WireSource::Signal(signal_a), "b")]);
+        get_signal_a(overridden).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -289,7 +370,7 @@ mod solution {
         let wires = get_input("wires").unwrap();
 
         let mut circuit = Circuit::new();
-        circuit.connect_wires(&wires);
+        circuit.connect_wires(&wires).unwrap();
 
         assert_eq!(circuit.queue.len(), circuit.wires_upstream.len());
         assert_eq!(circuit.queue.len(), circuit.wires_state.len());
@@ -306,7 +387,7 @@ mod solution {
         wires.replace_range(index..index + 10, "16076 -> b");
 
         let mut circuit = Circuit::new();
-        circuit.connect_wires(&wires);
+        circuit.connect_wires(&wires).unwrap();
 
         assert_eq!(circuit.queue.len(), circuit.wires_upstream.len());
         assert_eq!(circuit.queue.len(), circuit.wires_state.len());
@@ -315,4 +396,30 @@ mod solution {
         circuit.propagate_signals();
         assert_eq!(circuit.get_signal("a"), 2797);
     }
+
+    #[test]
+    fn connect_wires_invalid() {
+        let e = Circuit::new()
+            .connect_wires("123 -> x\nx XOR y -> z")
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 3: expected a gate, found `XOR`"
+        );
+
+        let e = Circuit::new().connect_wires("x LSHIFT -> y").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 14));
+    }
+
+    #[test]
+    fn parse_missing_wires() {
+        let e = Day7::parse("").err().unwrap();
+        assert_eq!(e.to_string(), "line 1, column 1: expected wire `a`");
+
+        let e = Day7::parse("123 -> x\nx -> b\n").err().unwrap();
+        assert_eq!(e.to_string(), "line 3, column 1: expected wire `a`");
+
+        let e = Day7::parse("123 -> x\nx OR y -> a\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 6, "y"));
+    }
 }
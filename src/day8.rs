@@ -1,46 +1,47 @@
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 #[allow(dead_code)]
 fn num_code_chars(characters: &str) -> usize {
     characters.len() - characters.lines().count() // Uncount newlines
 }
 
 #[allow(dead_code)]
-fn num_memory_chars(characters: &str) -> usize {
+fn num_memory_chars(characters: &str) -> Result<usize, ParseError> {
     let mut count: usize = 0;
-    for line in characters.lines() {
-        let line_length = line.len();
-        let mut line = line.as_bytes().iter();
+    for line in lines(characters) {
+        // Throw away beginning and ending double-quotation
+        let string = line
+            .text
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .ok_or_else(|| line.error(line.text, "expected a double-quoted string"))?;
+        let bytes = string.as_bytes();
 
-        line.next(); // Throw away beginning double-quotation
-        let mut i = 1;
-        while {
-            let c = line.next().unwrap();
-            match c {
-                b'\\' => {
-                    match line.next().unwrap() {
-                        b'\\' | b'"' => {
-                            count += 1;
-                            i += 2;
-                        }
-                        b'x' => {
-                            // Throw away hex digits
-                            line.next();
-                            line.next();
-                            count += 1;
-                            i += 4;
-                        }
-                        _ => unreachable!(),
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\\' {
+                i += match bytes.get(i + 1) {
+                    Some(b'\\' | b'"') => 2,
+                    // Hex escape, e.g. "\x27"
+                    Some(b'x')
+                        if bytes.len() >= i + 4
+                            && bytes[i + 2].is_ascii_hexdigit()
+                            && bytes[i + 3].is_ascii_hexdigit() =>
+                    {
+                        4
+                    }
+                    _ => {
+                        let escape = string.get(i..i + 2).unwrap_or(&string[i..]);
+                        return Err(line.error(escape, "invalid escape sequence"));
                     }
-                }
-                _ => {
-                    count += 1;
-                    i += 1;
-                }
+                };
+            } else {
+                i += 1;
             }
-            i < line_length
-        } {}
-        count -= 1; // Uncount ending double-quotation
+            count += 1;
+        }
     }
-    count
+    Ok(count)
 }
 
 #[allow(dead_code)]
@@ -59,6 +60,28 @@ fn num_encoded_chars(characters: &str) -> usize {
     count
 }
 
+pub struct Day8 {
+    characters: String,
+    memory_chars: usize,
+}
+
+impl Solution for Day8 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            characters: input.to_string(),
+            memory_chars: num_memory_chars(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        (num_code_chars(&self.characters) - self.memory_chars).into()
+    }
+
+    fn part2(&self) -> Answer {
+        (num_encoded_chars(&self.characters) - num_code_chars(&self.characters)).into()
+    }
+}
+
 #[cfg(test)]
 mod solution {
     use super::*;
@@ -68,7 +91,7 @@ mod solution {
     fn diff_num_code_num_memory_chars() {
         let characters = get_input("characters").unwrap();
         assert_eq!(
-            num_code_chars(&characters) - num_memory_chars(&characters),
+            num_code_chars(&characters) - num_memory_chars(&characters).unwrap(),
             1371
         );
     }
@@ -81,4 +104,19 @@ mod solution {
             2117
         );
     }
+
+    #[test]
+    fn num_memory_chars_invalid() {
+        let e = num_memory_chars("\"\\x27\"\n\"ab\\q\"").unwrap_err();
+        assert_eq!((e.line, e.column, e.token.as_str()), (2, 4, "\\q"));
+
+        let e = num_memory_chars("\"abc\\\"").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "line 1, column 5: invalid escape sequence, found `\\`"
+        );
+
+        let e = num_memory_chars("abc").unwrap_err();
+        assert_eq!(e.column, 1);
+    }
 }
@@ -1,5 +1,7 @@
 /// This is a Hamiltonian path problem. We know that multiple Hamiltonian
 /// paths exist and want to determine the length of the shortest one.
+use crate::parse::{lines, ParseError};
+use crate::solution::{Answer, Solution};
 use rustc_hash::FxHashMap;
 
 /// Use a variation of the dynamic programming approach to solving the Hamiltonian path problem.
@@ -49,20 +51,22 @@ where
 /// Build the adjacency matrix adj. This tells us the distance between every node.
 /// It is symmetric because the graph is undirected.
 #[allow(dead_code)]
-fn build_adjacency_matrix(locations: &str) -> Vec<Vec<u32>> {
+fn build_adjacency_matrix(locations: &str) -> Result<Vec<Vec<u32>>, ParseError> {
     let mut adj: Vec<Vec<u32>> = Vec::new();
     // Asign each location name an index starting with 0
     let mut index = 0;
     let mut locations_index: FxHashMap<&str, usize> = FxHashMap::default();
 
-    for line in locations.lines() {
+    for line in lines(locations) {
         // Line example: "AlphaCentauri to Snowdin = 66"
-        let mut line_split = line.split(" = ");
-        let location_names = line_split.next().unwrap();
-        let mut location_names_split = location_names.split(" to ");
-        let location1 = location_names_split.next().unwrap();
-        let location2 = location_names_split.next().unwrap();
-        let distance: u32 = line_split.next().unwrap().parse::<u32>().unwrap();
+        let (location_names, distance) = line
+            .text
+            .split_once(" = ")
+            .ok_or_else(|| line.error("", "expected `=`"))?;
+        let (location1, location2) = location_names
+            .split_once(" to ")
+            .ok_or_else(|| line.error(location_names, "expected `<location> to <location>`"))?;
+        let distance: u32 = line.parse(distance, "a distance")?;
 
         // Assign location name an index if unassigned
         if !locations_index.contains_key(location1) {
@@ -87,7 +91,42 @@ fn build_adjacency_matrix(locations: &str) -> Vec<Vec<u32>> {
         adj[*location1_index][*location2_index] = distance;
         adj[*location2_index][*location1_index] = distance;
     }
-    adj
+    Ok(adj)
+}
+
+/// Length of the shortest (`std::cmp::min`) or longest (`std::cmp::max`) path visiting every
+/// location.
+fn length_best_path<F>(adj: Vec<Vec<u32>>, comparator: F) -> Option<u32>
+where
+    F: Fn(u32, u32) -> u32 + Copy,
+{
+    let n = adj.len();
+    let dp = length_hamiltonian_paths(adj, comparator);
+    // Retrieve the path length from the subset containing all nodes
+    dp[(1 << n) - 1]
+        .iter()
+        .map(|v| v.unwrap()) // Path ending with all nodes will exist
+        .reduce(comparator)
+}
+
+pub struct Day9 {
+    adj: Vec<Vec<u32>>,
+}
+
+impl Solution for Day9 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            adj: build_adjacency_matrix(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        length_best_path(self.adj.clone(), std::cmp::min).into()
+    }
+
+    fn part2(&self) -> Answer {
+        length_best_path(self.adj.clone(), std::cmp::max).into()
+    }
 }
 
 #[cfg(test)]
@@ -98,44 +137,20 @@ mod solution {
     #[test]
     fn get_length_of_shortest_hamiltonian_path() {
         let locations = get_input("locations").unwrap();
-        let adj = build_adjacency_matrix(&locations);
-        let n = adj.len();
-        let dp = length_hamiltonian_paths(adj, std::cmp::min);
-        // Retrieve shortest path length from subset containing all nodes
-        let length_shortest_path = dp[(1 << n) - 1]
-            .iter()
-            .map(|v| v.unwrap()) // Path ending with all nodes will exist
-            .min()
-            .unwrap();
-        assert_eq!(length_shortest_path, 141);
+        let adj = build_adjacency_matrix(&locations).unwrap();
+        assert_eq!(length_best_path(adj, std::cmp::min), Some(141));
     }
 
     #[test]
     fn get_length_of_longest_hamiltonian_path() {
         let locations = get_input("locations").unwrap();
-        let adj = build_adjacency_matrix(&locations);
-        let n = adj.len();
-        let dp = length_hamiltonian_paths(adj, std::cmp::max);
-        // Retrieve longest path length from subset containing all nodes
-        let length_longest_path = dp[(1 << n) - 1]
-            .iter()
-            .map(|v| v.unwrap()) // Path ending with all nodes will exist
-            .max()
-            .unwrap();
-        assert_eq!(length_longest_path, 736);
+        let adj = build_adjacency_matrix(&locations).unwrap();
+        assert_eq!(length_best_path(adj, std::cmp::max), Some(736));
     }
 
     #[test]
     fn test_length_of_shortest_hamiltonian_path_simple() {
         let adj = vec![vec![0, 464, 518], vec![464, 0, 141], vec![518, 141, 0]];
-        let n = adj.len();
-        let dp = length_hamiltonian_paths(adj, std::cmp::min);
-        // Retrieve shortest path length from subset containing all nodes
-        let length_shortest_path = dp[(1 << n) - 1]
-            .iter()
-            .map(|v| v.unwrap()) // Path ending with all nodes will exist
-            .min()
-            .unwrap();
-        assert_eq!(length_shortest_path, 605);
+        assert_eq!(length_best_path(adj, std::cmp::min), Some(605));
     }
 }
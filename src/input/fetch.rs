@@ -0,0 +1,220 @@
+//! Download puzzle inputs into a per-user cache.
+use super::get_input::input_path;
+use crate::Day;
+use md5::{Digest, Md5};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding the adventofcode.com `session` cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable naming the user whose cached inputs are read, instead of the session.
+pub const USER_VAR: &str = "AOC_USER";
+/// Environment variable overriding the cache directory, `.cache/inputs` by default.
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2015;
+
+/// Fetches a page with the session cookie set.
+pub trait HttpClient {
+    fn get(&self, url: &str, session: &str) -> Result<String, Box<dyn Error>>;
+}
+
+/// Blocking client backed by `ureq`.
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqClient {
+    fn default() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!(
+                    env!("CARGO_PKG_NAME"),
+                    "/",
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .build(),
+        }
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, session: &str) -> Result<String, Box<dyn Error>> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(|e| format!("{}: {}", url, e))?;
+        Ok(response.into_string()?)
+    }
+}
+
+/// Downloads inputs, caching them under a directory per user. A user's cache directory is laid
+/// out like `src/input`, so it can also be used as `AOC_INPUT_DIR`.
+pub struct Fetcher<C: HttpClient> {
+    client: C,
+    session: String,
+    base_url: String,
+    cache_dir: PathBuf,
+}
+
+impl Fetcher<UreqClient> {
+    /// Fetch over HTTPS with the session in `AOC_SESSION`.
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        let session =
+            env::var(SESSION_VAR).map_err(|_| format!("Set `{}` to fetch inputs", SESSION_VAR))?;
+        Ok(Self::new(UreqClient::default(), session.trim()))
+    }
+}
+
+impl<C: HttpClient> Fetcher<C> {
+    pub fn new(client: C, session: &str) -> Self {
+        Self {
+            client,
+            session: session.to_string(),
+            base_url: BASE_URL.to_string(),
+            cache_dir: cache_root().join(user_key(session)),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Read the day's input from the cache, downloading it first if needed.
+    pub fn fetch(&self, day: &Day) -> Result<String, Box<dyn Error>> {
+        let path = input_path(&self.cache_dir, day.input);
+        if path.exists() {
+            return Ok(fs::read_to_string(path)?);
+        }
+        let url = format!("{}/{}/day/{}/input", self.base_url, YEAR, day.day);
+        let input = self.client.get(&url, &self.session)?;
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(&path, &input).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(input)
+    }
+}
+
+/// The cache directory holding every user's inputs. Relative paths are resolved against the
+/// crate root.
+pub fn cache_root() -> PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match env::var_os(CACHE_DIR_VAR) {
+        Some(dir) => root.join(dir),
+        None => root.join(".cache/inputs"),
+    }
+}
+
+/// Name a user's cache directory after a hash of their session, so the session itself is not
+/// written to disk.
+pub fn user_key(session: &str) -> String {
+    let digest = Md5::digest(session.trim());
+    format!("{:x}", digest)[..12].to_string()
+}
+
+/// The cache directory of the user in `AOC_USER`, or else of the session in `AOC_SESSION`.
+pub fn user_cache_dir() -> Option<PathBuf> {
+    let user = env::var(USER_VAR)
+        .ok()
+        .or_else(|| env::var(SESSION_VAR).ok().map(|session| user_key(&session)))?;
+    Some(cache_root().join(user))
+}
+
+#[cfg(test)]
+mod fetcher {
+    use super::*;
+    use crate::get_day;
+    use std::cell::RefCell;
+    use std::thread;
+
+    /// Records requested urls and answers with a canned input.
+    #[derive(Default)]
+    struct FakeClient {
+        requests: RefCell<Vec<(String, String)>>,
+    }
+
+    impl HttpClient for FakeClient {
+        fn get(&self, url: &str, session: &str) -> Result<String, Box<dyn Error>> {
+            self.requests
+                .borrow_mut()
+                .push((url.to_string(), session.to_string()));
+            Ok("()())\n".to_string())
+        }
+    }
+
+    #[test]
+    fn test_fetch_caches_input() {
+        let cache = tempfile::tempdir().unwrap();
+        let fetcher = Fetcher::new(FakeClient::default(), "abc").with_cache_dir(cache.path());
+        let day = get_day(1).unwrap();
+
+        assert_eq!(fetcher.fetch(day).unwrap(), "()())\n");
+        assert_eq!(fetcher.fetch(day).unwrap(), "()())\n");
+        assert_eq!(
+            *fetcher.client.requests.borrow(),
+            [(
+                "https://adventofcode.com/2015/day/1/input".to_string(),
+                "abc".to_string()
+            )]
+        );
+        assert_eq!(
+            fs::read_to_string(cache.path().join("directions.txt")).unwrap(),
+            "()())\n"
+        );
+    }
+
+    #[test]
+    fn test_user_key() {
+        assert_eq!(user_key("abc"), user_key("abc\n"));
+        assert_ne!(user_key("abc"), user_key("abd"));
+        assert_eq!(user_key("abc").len(), 12);
+    }
+
+    #[test]
+    fn test_fetch_from_local_server() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        let handle = thread::spawn(move || {
+            for _ in 0..2 {
+                let request = server.recv().unwrap();
+                let cookie = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Cookie"))
+                    .map(|header| header.value.to_string());
+                let response = match (request.url(), cookie.as_deref()) {
+                    ("/2015/day/7/input", Some("session=abc")) => {
+                        tiny_http::Response::from_string("123 -> a\n")
+                    }
+                    _ => tiny_http::Response::from_string("Not found").with_status_code(404),
+                };
+                request.respond(response).unwrap();
+            }
+        });
+
+        let cache = tempfile::tempdir().unwrap();
+        let fetcher = Fetcher::new(UreqClient::default(), "abc")
+            .with_base_url(&base_url)
+            .with_cache_dir(cache.path());
+        assert_eq!(fetcher.fetch(get_day(7).unwrap()).unwrap(), "123 -> a\n");
+
+        // Nothing is cached for days that fail to download
+        assert!(fetcher.fetch(get_day(8).unwrap()).is_err());
+        assert!(!cache.path().join("characters.txt").exists());
+        handle.join().unwrap();
+    }
+}
@@ -1,7 +1,86 @@
+use super::fetch::user_cache_dir;
+use std::env;
 use std::error::Error;
 use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
+/// Environment variable overriding the directory inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Read an input set from the directory in `AOC_INPUT_DIR`, defaulting to `src/input`, falling
+/// back to the fetched inputs of the current user. `-` reads stdin instead.
 #[allow(dead_code)]
 pub fn get_input(input: &str) -> Result<String, Box<dyn Error>> {
-    Ok(read_to_string(format!("src/input/{}.txt", input))?)
+    get_input_from(&find_input_dir(input), input)
+}
+
+/// The input directory, or the current user's cache if the input is only there.
+pub fn find_input_dir(input: &str) -> PathBuf {
+    let dir = input_dir();
+    if input == "-" || dir == Path::new("-") || input_path(&dir, input).exists() {
+        return dir;
+    }
+    user_cache_dir()
+        .filter(|cache| input_path(cache, input).exists())
+        .unwrap_or(dir)
+}
+
+/// Read an input set from `dir`. `-` as the input or the directory reads stdin instead.
+pub fn get_input_from(dir: &Path, input: &str) -> Result<String, Box<dyn Error>> {
+    if input == "-" || dir == Path::new("-") {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        return Ok(buffer);
+    }
+    let path = input_path(dir, input);
+    read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+/// The directory inputs are read from, see `INPUT_DIR_VAR`.
+pub fn input_dir() -> PathBuf {
+    resolve_input_dir(env::var_os(INPUT_DIR_VAR).as_deref().map(Path::new))
+}
+
+/// Relative directories are resolved against the crate root, so inputs are found whatever the
+/// working directory.
+pub fn resolve_input_dir(dir: Option<&Path>) -> PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match dir {
+        Some(dir) if dir == Path::new("-") => dir.to_path_buf(),
+        Some(dir) => root.join(dir),
+        None => root.join("src/input"),
+    }
+}
+
+pub fn input_path(dir: &Path, input: &str) -> PathBuf {
+    dir.join(format!("{}.txt", input))
+}
+
+#[cfg(test)]
+mod input_dir {
+    use super::*;
+
+    #[test]
+    fn test_resolve_input_dir() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(resolve_input_dir(None), root.join("src/input"));
+        assert_eq!(
+            resolve_input_dir(Some(Path::new("inputs/alice"))),
+            root.join("inputs/alice")
+        );
+        assert_eq!(
+            resolve_input_dir(Some(Path::new("/tmp/aoc"))),
+            Path::new("/tmp/aoc")
+        );
+        assert_eq!(resolve_input_dir(Some(Path::new("-"))), Path::new("-"));
+    }
+
+    #[test]
+    fn test_get_input_from() {
+        let dir = resolve_input_dir(None);
+        assert_eq!(get_input_from(&dir, "secret").unwrap(), "ckczppom\n");
+        let e = get_input_from(&dir, "missing").unwrap_err();
+        assert!(e.to_string().contains("missing.txt"));
+    }
 }
@@ -1,7 +1,8 @@
-#![feature(slice_as_chunks)]
-#![feature(new_uninit)]
-#![feature(array_windows)]
-mod day1;
+// Most of these are stable on recent toolchains, but are still needed by older nightlies.
+#![cfg_attr(feature = "nightly", allow(stable_features))]
+#![cfg_attr(feature = "nightly", feature(new_uninit, array_windows, portable_simd))]
+mod compat;
+pub mod day1;
 mod day10;
 mod day11;
 mod day12;
@@ -12,18 +13,215 @@ mod day16;
 mod day17;
 mod day18;
 mod day19;
-mod day2;
+pub mod day2;
 mod day20;
 mod day21;
 mod day22;
 mod day23;
 mod day24;
 mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
 mod day7;
 mod day8;
 mod day9;
-mod input;
+pub mod input;
+pub mod parse;
+pub mod run_all;
+pub mod solution;
+pub mod verify;
+
+pub use parse::ParseError;
+pub use solution::{Answer, Solution};
+
+/// A day in the registry.
+pub struct Day {
+    pub day: u8,
+    /// The name of the input file under `src/input`.
+    pub input: &'static str,
+    parse: fn(&str) -> Result<Box<dyn Solution>, ParseError>,
+}
+
+impl Day {
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solution>, ParseError> {
+        (self.parse)(input)
+    }
+}
+
+fn boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solution>, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+/// Every day's solution, in order.
+pub static DAYS: [Day; 25] = [
+    Day {
+        day: 1,
+        input: "directions",
+        parse: boxed::<day1::Day1>,
+    },
+    Day {
+        day: 2,
+        input: "presents",
+        parse: boxed::<day2::Day2>,
+    },
+    Day {
+        day: 3,
+        input: "house_directions",
+        parse: boxed::<day3::Day3>,
+    },
+    Day {
+        day: 4,
+        input: "secret",
+        parse: boxed::<day4::Day4>,
+    },
+    Day {
+        day: 5,
+        input: "strings",
+        parse: boxed::<day5::Day5>,
+    },
+    Day {
+        day: 6,
+        input: "instructions",
+        parse: boxed::<day6::Day6>,
+    },
+    Day {
+        day: 7,
+        input: "wires",
+        parse: boxed::<day7::Day7>,
+    },
+    Day {
+        day: 8,
+        input: "characters",
+        parse: boxed::<day8::Day8>,
+    },
+    Day {
+        day: 9,
+        input: "locations",
+        parse: boxed::<day9::Day9>,
+    },
+    Day {
+        day: 10,
+        input: "sequence",
+        parse: boxed::<day10::Day10>,
+    },
+    Day {
+        day: 11,
+        input: "password",
+        parse: boxed::<day11::Day11>,
+    },
+    Day {
+        day: 12,
+        input: "numbers_json",
+        parse: boxed::<day12::Day12>,
+    },
+    Day {
+        day: 13,
+        input: "happiness",
+        parse: boxed::<day13::Day13>,
+    },
+    Day {
+        day: 14,
+        input: "reindeers",
+        parse: boxed::<day14::Day14>,
+    },
+    Day {
+        day: 15,
+        input: "cookie_properties",
+        parse: boxed::<day15::Day15>,
+    },
+    Day {
+        day: 16,
+        input: "sues",
+        parse: boxed::<day16::Day16>,
+    },
+    Day {
+        day: 17,
+        input: "containers",
+        parse: boxed::<day17::Day17>,
+    },
+    Day {
+        day: 18,
+        input: "light_grid",
+        parse: boxed::<day18::Day18>,
+    },
+    Day {
+        day: 19,
+        input: "replacements",
+        parse: boxed::<day19::Day19>,
+    },
+    Day {
+        day: 20,
+        input: "min_presents",
+        parse: boxed::<day20::Day20>,
+    },
+    Day {
+        day: 21,
+        input: "rpg_boss",
+        parse: boxed::<day21::Day21>,
+    },
+    Day {
+        day: 22,
+        input: "wizard_boss",
+        parse: boxed::<day22::Day22>,
+    },
+    Day {
+        day: 23,
+        input: "program",
+        parse: boxed::<day23::Day23>,
+    },
+    Day {
+        day: 24,
+        input: "packages",
+        parse: boxed::<day24::Day24>,
+    },
+    Day {
+        day: 25,
+        input: "code_grid",
+        parse: boxed::<day25::Day25>,
+    },
+];
+
+pub fn get_day(day: u8) -> Option<&'static Day> {
+    DAYS.get((day as usize).wrapping_sub(1))
+}
+
+#[cfg(test)]
+mod registry {
+    use super::*;
+    use crate::input::get_input::{get_input, get_input_from, resolve_input_dir};
+
+    #[test]
+    fn registry_in_order() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, i + 1);
+        }
+        assert!(get_day(0).is_none());
+        assert!(get_day(26).is_none());
+    }
+
+    #[test]
+    fn solve_from_registry() {
+        let day = get_day(7).unwrap();
+        let solution = day.parse(&get_input(day.input).unwrap()).unwrap();
+        assert_eq!(solution.part1(), Answer::UInt(16076));
+        assert_eq!(solution.part2(), Answer::UInt(2797));
+    }
+
+    #[test]
+    fn solve_every_day_from_registry() {
+        let dir = resolve_input_dir(None);
+        let manifest = verify::Manifest::load(&dir).unwrap();
+        for day in &DAYS {
+            let solution = day
+                .parse(&get_input_from(&dir, day.input).unwrap())
+                .unwrap();
+            for (part, answer) in [(1, solution.part1()), (2, solution.part2())] {
+                if let Some(expected) = manifest.expected(day.day, part) {
+                    assert_eq!(answer, *expected, "day {} part {}", day.day, part);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Errors for malformed puzzle inputs, pointing at the offending token.
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based byte column of the start of the token.
+    pub column: usize,
+    /// The offending token, empty if a token is missing.
+    pub token: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// `token` should be a slice of `line`, otherwise the error points at the end of the line.
+    pub fn new(line_number: usize, line: &str, token: &str, message: impl Into<String>) -> Self {
+        let line_start = line.as_ptr() as usize;
+        let token_start = token.as_ptr() as usize;
+        let column =
+            if token_start >= line_start && token_start + token.len() <= line_start + line.len() {
+                token_start - line_start + 1
+            } else {
+                line.len() + 1
+            };
+        Self {
+            line: line_number,
+            column,
+            token: token.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Move the error to another line. Parsers that only see a single line report line 1.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.token.is_empty() {
+            write!(f, ", found `{}`", self.token)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// A line of input and its line number.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// A line parsed on its own.
+    pub fn single(text: &'a str) -> Self {
+        Self { number: 1, text }
+    }
+
+    pub fn error(&self, token: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, self.text, token, message)
+    }
+
+    /// Fail with "expected `what`" if the token is missing.
+    pub fn expect(&self, token: Option<&'a str>, what: &str) -> Result<&'a str, ParseError> {
+        token.ok_or_else(|| self.error("", format!("expected {}", what)))
+    }
+
+    /// Fail unless the token is exactly `expected`.
+    pub fn expect_literal(&self, token: Option<&'a str>, expected: &str) -> Result<(), ParseError> {
+        let token = self.expect(token, &format!("`{}`", expected))?;
+        if token != expected {
+            return Err(self.error(token, format!("expected `{}`", expected)));
+        }
+        Ok(())
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str, what: &str) -> Result<T, ParseError> {
+        token
+            .parse()
+            .map_err(|_| self.error(token, format!("expected {}", what)))
+    }
+
+    /// Parse the next token, failing if it is missing or invalid.
+    pub fn parse_next<T: FromStr>(
+        &self,
+        tokens: &mut impl Iterator<Item = &'a str>,
+        what: &str,
+    ) -> Result<T, ParseError> {
+        let token = self.expect(tokens.next(), what)?;
+        self.parse(token, what)
+    }
+
+    /// Fail if there are tokens left over.
+    pub fn expect_end(&self, tokens: &mut impl Iterator<Item = &'a str>) -> Result<(), ParseError> {
+        match tokens.next() {
+            Some(token) => Err(self.error(token, "unexpected trailing input")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Number the lines of the input, starting at 1.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+/// Parse every line with `T::from_str`, fixing up the line numbers of errors.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    lines(input)
+        .map(|line| {
+            line.text
+                .parse()
+                .map_err(|e: ParseError| e.on_line(line.number))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod parse_error {
+    use super::*;
+
+    #[test]
+    fn test_column_of_token() {
+        let input = "jio a, +19\ninc c";
+        let line = lines(input).nth(1).unwrap();
+        let error = line.error(&line.text[4..], "expected register `a` or `b`");
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected register `a` or `b`, found `c`"
+        );
+    }
+
+    #[test]
+    fn test_missing_token() {
+        let line = Line::single("hlf");
+        let mut tokens = line.text.split(' ').skip(1);
+        let error = line
+            .parse_next::<u8>(&mut tokens, "a register")
+            .unwrap_err();
+        assert_eq!(error.column, 4);
+        assert_eq!(error.to_string(), "line 1, column 4: expected a register");
+    }
+}
@@ -0,0 +1,225 @@
+//! Run many days concurrently and report where the time goes.
+use crate::input::get_input::{find_input_dir, get_input_from};
+use crate::{Answer, Day};
+use rayon::prelude::*;
+use serde_json::json;
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// A day's answers and timings.
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: u8,
+    pub answers: [Answer; 2],
+    pub parse: Duration,
+    pub parts: [Duration; 2],
+    /// Why the day couldn't be run, if it wasn't. The answers are then `Answer::None`.
+    pub error: Option<String>,
+}
+
+impl DayRun {
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts[0] + self.parts[1]
+    }
+}
+
+/// The runs of every day, and the wall-clock time they took together.
+#[derive(Debug)]
+pub struct RunAll {
+    pub runs: Vec<DayRun>,
+    pub elapsed: Duration,
+}
+
+fn run_day(day: &Day, dir: Option<&Path>) -> Result<DayRun, String> {
+    let dir = dir.map_or_else(|| find_input_dir(day.input), Path::to_path_buf);
+    let input = get_input_from(&dir, day.input).map_err(|e| format!("Day {}: {}", day.day, e))?;
+
+    let start = Instant::now();
+    let solution = day
+        .parse(&input)
+        .map_err(|e| format!("Day {}: {}", day.day, e))?;
+    let parse = start.elapsed();
+    let start = Instant::now();
+    let part1 = solution.part1();
+    let part1_time = start.elapsed();
+    let start = Instant::now();
+    let part2 = solution.part2();
+    let part2_time = start.elapsed();
+
+    Ok(DayRun {
+        day: day.day,
+        answers: [part1, part2],
+        parse,
+        parts: [part1_time, part2_time],
+        error: None,
+    })
+}
+
+/// Parse and solve each day as a task on a pool of `threads` threads (0 for one per core).
+/// Inputs are read from `dir`, or found as by `get_input` if `None`. Days whose input is missing
+/// or invalid are reported as errors, without stopping the other days.
+pub fn run_all(
+    days: &[&Day],
+    dir: Option<&Path>,
+    threads: usize,
+) -> Result<RunAll, Box<dyn Error>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()?;
+    let start = Instant::now();
+    let runs = pool.install(|| {
+        days.par_iter()
+            .map(|day| {
+                run_day(day, dir).unwrap_or_else(|e| DayRun {
+                    day: day.day,
+                    answers: [Answer::None, Answer::None],
+                    parse: Duration::ZERO,
+                    parts: [Duration::ZERO; 2],
+                    error: Some(e),
+                })
+            })
+            .collect()
+    });
+    Ok(RunAll {
+        runs,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Render a table of timings per day, with each day's share of the summed time as a bar.
+pub fn report(run_all: &RunAll) -> String {
+    const BAR_WIDTH: f64 = 30.0;
+    let sum: Duration = run_all.runs.iter().map(DayRun::total).sum();
+    let mut table = String::new();
+    writeln!(
+        table,
+        "Day  {:>10}  {:>10}  {:>10}  {:>10}  Share",
+        "Parse", "Part 1", "Part 2", "Total"
+    )
+    .unwrap();
+    for run in &run_all.runs {
+        // Errors are shown instead of the timings
+        if let Some(error) = &run.error {
+            writeln!(table, "{:>3}  ERROR  {}", run.day, error).unwrap();
+            continue;
+        }
+        let share = run.total().as_secs_f64() / sum.as_secs_f64().max(f64::MIN_POSITIVE);
+        writeln!(
+            table,
+            "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5.1}% {}",
+            run.day,
+            format!("{:.2?}", run.parse),
+            format!("{:.2?}", run.parts[0]),
+            format!("{:.2?}", run.parts[1]),
+            format!("{:.2?}", run.total()),
+            share * 100.0,
+            "#".repeat((share * BAR_WIDTH).round() as usize)
+        )
+        .unwrap();
+    }
+    write!(
+        table,
+        "Total {:.2?} across {} days",
+        sum,
+        run_all.runs.len()
+    )
+    .unwrap();
+    let errors = run_all
+        .runs
+        .iter()
+        .filter(|run| run.error.is_some())
+        .count();
+    if errors > 0 {
+        write!(table, ", {} errors", errors).unwrap();
+    }
+    write!(table, ", {:.2?} wall-clock", run_all.elapsed).unwrap();
+    table
+}
+
+/// The answers and timings (in seconds) of every day as JSON.
+pub fn to_json(run_all: &RunAll) -> serde_json::Value {
+    let days: Vec<_> = run_all
+        .runs
+        .iter()
+        .map(|run| {
+            json!({
+                "day": run.day,
+                "part1": &run.answers[0],
+                "part2": &run.answers[1],
+                "parse_secs": run.parse.as_secs_f64(),
+                "part1_secs": run.parts[0].as_secs_f64(),
+                "part2_secs": run.parts[1].as_secs_f64(),
+                "error": &run.error,
+            })
+        })
+        .collect();
+    json!({ "days": days, "elapsed_secs": run_all.elapsed.as_secs_f64() })
+}
+
+#[cfg(test)]
+mod run_days {
+    use super::*;
+    use crate::get_day;
+    use crate::input::get_input::resolve_input_dir;
+    use std::fs;
+
+    #[test]
+    fn test_run_all() {
+        let days = [1, 2, 7].map(|day| get_day(day).unwrap());
+        let run_all = super::run_all(&days, None, 2).unwrap();
+        let days: Vec<_> = run_all.runs.iter().map(|run| run.day).collect();
+        assert_eq!(days, [1, 2, 7]);
+        assert_eq!(
+            run_all.runs[2].answers,
+            [Answer::UInt(16076), Answer::UInt(2797)]
+        );
+
+        let report = report(&run_all);
+        assert_eq!(report.lines().count(), 5);
+        assert!(report.ends_with("wall-clock"));
+
+        let json = to_json(&run_all);
+        assert_eq!(json["days"][2]["part1"], 16076);
+        assert_eq!(json["days"][2]["part2"], 2797);
+    }
+
+    #[test]
+    fn test_run_all_missing_input() {
+        let days = [get_day(1).unwrap()];
+        let run_all = run_all(&days, Some(Path::new("/nonexistent")), 1).unwrap();
+        let error = run_all.runs[0].error.as_deref().unwrap();
+        assert!(error.starts_with("Day 1: "));
+        assert_eq!(run_all.runs[0].answers, [Answer::None, Answer::None]);
+
+        let report = report(&run_all);
+        assert!(report.contains(&format!("  1  ERROR  {}", error)));
+        assert!(report.contains("across 1 days, 1 errors, "));
+
+        let json = to_json(&run_all);
+        assert_eq!(json["days"][0]["error"], error);
+    }
+
+    #[test]
+    fn test_run_all_keeps_going_after_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let inputs = resolve_input_dir(None);
+        fs::copy(inputs.join("wires.txt"), dir.path().join("wires.txt")).unwrap();
+        fs::write(dir.path().join("instructions.txt"), "toggle 0,0 thru 1,1\n").unwrap();
+
+        let days = [3, 7, 6].map(|day| get_day(day).unwrap());
+        let run_all = run_all(&days, Some(dir.path()), 2).unwrap();
+        let errors: Vec<_> = run_all.runs.iter().map(|run| run.error.is_some()).collect();
+        assert_eq!(errors, [true, false, true]);
+        assert_eq!(run_all.runs[1].answers[0], Answer::UInt(16076));
+        assert!(run_all.runs[2]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("Day 6: line 1"));
+
+        let json = to_json(&run_all);
+        assert!(json["days"][1]["error"].is_null());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::parse::ParseError;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// A day's puzzle. `parse` does all the input processing up front so the two parts can be
+/// solved (and timed) independently.
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized;
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+}
+
+/// The answer to one part of a puzzle. Serializes as a JSON number, string or `null`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    /// There is no answer, e.g. day 25 has no second part.
+    None,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "none"),
+        }
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(n) => serializer.serialize_i64(*n),
+            Answer::UInt(n) => serializer.serialize_u64(*n),
+            Answer::Text(s) => serializer.serialize_str(s),
+            Answer::None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Compare with an expected answer as written in a manifest, ignoring surrounding whitespace.
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        let other = other.trim();
+        match self {
+            Answer::Int(n) => other.parse() == Ok(*n),
+            Answer::UInt(n) => other.parse() == Ok(*n),
+            Answer::Text(s) => s == other,
+            Answer::None => other == "none",
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl From<&Answer> for serde_json::Value {
+    fn from(answer: &Answer) -> Self {
+        serde_json::to_value(answer).unwrap()
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident, $into:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $into)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(Int, i64, i32, i64);
+impl_from_int!(UInt, u64, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+#[cfg(test)]
+mod answer {
+    use super::*;
+
+    #[test]
+    fn test_answer_from() {
+        assert_eq!(Answer::from(-3i32), Answer::Int(-3));
+        assert_eq!(Answer::from(16076u16), Answer::UInt(16076));
+        assert_eq!(Answer::from(Some(1783usize)), Answer::UInt(1783));
+        assert_eq!(Answer::from(None::<usize>), Answer::None);
+        assert_eq!(
+            Answer::from(String::from("cqjxxyzz")).to_string(),
+            "cqjxxyzz"
+        );
+    }
+
+    #[test]
+    fn test_answer_eq_str() {
+        assert_eq!(Answer::Int(-3), "-3");
+        assert_eq!(Answer::UInt(16076), " 16076\n");
+        assert_ne!(Answer::UInt(16076), "16077");
+        assert_ne!(Answer::UInt(1), "-1");
+        assert_eq!(Answer::Text("cqjxxyzz".to_string()), "cqjxxyzz");
+        assert_eq!(Answer::None, "none");
+        assert_ne!(Answer::None, "0");
+    }
+
+    #[test]
+    fn test_answer_to_json() {
+        let answers = [
+            Answer::Int(-3),
+            Answer::UInt(11266889531),
+            Answer::Text("cqjxxyzz".to_string()),
+            Answer::None,
+        ];
+        assert_eq!(
+            serde_json::to_string(&answers).unwrap(),
+            r#"[-3,11266889531,"cqjxxyzz",null]"#
+        );
+        assert_eq!(
+            serde_json::Value::from(&Answer::UInt(7)),
+            serde_json::json!(7)
+        );
+    }
+}
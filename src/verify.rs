@@ -0,0 +1,325 @@
+//! Check every day's answers against the `answers.toml` manifest of an input set.
+use crate::input::get_input::get_input_from;
+use crate::{Answer, Day, DAYS};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The name of the manifest in an input directory.
+pub const MANIFEST: &str = "answers.toml";
+
+/// Expected answers keyed by (day, part), read from a manifest of the form
+///
+/// ```toml
+/// [day1]
+/// part1 = 232
+/// part2 = 1783
+///
+/// [day11]
+/// part1 = "cqjxxyzz"
+/// ```
+///
+/// Parts without an expected answer are skipped.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+impl Manifest {
+    pub fn parse(manifest: &str) -> Result<Self, Box<dyn Error>> {
+        let table: toml::Table = manifest.parse()?;
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in &table {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("Invalid day `{}`, expected e.g. `day1`", day_key))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("`{}` should be a table of parts", day_key))?;
+            for (part_key, expected) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => return Err(format!("Invalid part `{}.{}`", day_key, part_key).into()),
+                };
+                let expected = match expected {
+                    toml::Value::Integer(n) => n.to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    _ => {
+                        return Err(format!(
+                            "`{}.{}` should be a number or string",
+                            day_key, part_key
+                        )
+                        .into())
+                    }
+                };
+                answers.insert((day, part), expected);
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    /// Load the manifest from an input directory.
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = dir.join(MANIFEST);
+        let manifest = read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&manifest).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The manifest has no answer for this part.
+    Unchecked,
+    /// The day's input couldn't be read or parsed.
+    Error,
+}
+
+/// The outcome of solving one part.
+#[derive(Debug)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub expected: Option<String>,
+    pub time: Duration,
+    /// Why the part couldn't be solved, if it wasn't.
+    pub error: Option<String>,
+}
+
+impl PartResult {
+    pub fn status(&self) -> Status {
+        if self.error.is_some() {
+            return Status::Error;
+        }
+        match &self.expected {
+            Some(expected) if self.answer == **expected => Status::Pass,
+            Some(_) => Status::Fail,
+            None => Status::Unchecked,
+        }
+    }
+}
+
+/// Solve both parts of a day with the input from `dir`. Parsing time is included in part 1.
+pub fn verify_day(
+    day: &Day,
+    dir: &Path,
+    manifest: &Manifest,
+) -> Result<Vec<PartResult>, Box<dyn Error>> {
+    let input = get_input_from(dir, day.input)?;
+    let start = Instant::now();
+    let solution = day
+        .parse(&input)
+        .map_err(|e| format!("Day {}: {}", day.day, e))?;
+    let parse_time = start.elapsed();
+
+    let mut results = Vec::new();
+    for part in [1, 2] {
+        let start = Instant::now();
+        let answer = match part {
+            1 => solution.part1(),
+            _ => solution.part2(),
+        };
+        let mut time = start.elapsed();
+        if part == 1 {
+            time += parse_time;
+        }
+        results.push(PartResult {
+            day: day.day,
+            part,
+            answer,
+            expected: manifest.expected(day.day, part).map(String::from),
+            time,
+            error: None,
+        });
+    }
+    Ok(results)
+}
+
+/// Solve every day with the inputs and manifest in `dir`. Days whose input is missing or
+/// invalid are reported as errors, without stopping the other days.
+pub fn verify(dir: &Path) -> Result<Vec<PartResult>, Box<dyn Error>> {
+    verify_days(&DAYS.iter().collect::<Vec<_>>(), dir)
+}
+
+/// Solve the given days with the inputs and manifest in `dir`, see `verify`.
+pub fn verify_days(days: &[&Day], dir: &Path) -> Result<Vec<PartResult>, Box<dyn Error>> {
+    let manifest = Manifest::load(dir)?;
+    let mut results = Vec::new();
+    for day in days {
+        match verify_day(day, dir, &manifest) {
+            Ok(day_results) => results.extend(day_results),
+            Err(e) => results.extend([1, 2].map(|part| PartResult {
+                day: day.day,
+                part,
+                answer: Answer::None,
+                expected: manifest.expected(day.day, part).map(String::from),
+                time: Duration::ZERO,
+                error: Some(e.to_string()),
+            })),
+        }
+    }
+    Ok(results)
+}
+
+/// Render the results as a table, with a summary line.
+pub fn report(results: &[PartResult]) -> String {
+    let mut table = String::new();
+    let answer_width = results
+        .iter()
+        .map(|result| result.answer.to_string().len())
+        .chain(
+            results
+                .iter()
+                .flat_map(|result| result.expected.as_ref().map(String::len)),
+        )
+        .max()
+        .unwrap_or(0)
+        .max("Expected".len());
+    writeln!(
+        table,
+        "Day Part  {:<w$}  {:<w$}  Status  Time",
+        "Answer",
+        "Expected",
+        w = answer_width
+    )
+    .unwrap();
+    for result in results {
+        let status = match result.status() {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Unchecked => "-",
+            Status::Error => "ERROR",
+        };
+        // Errors are shown instead of the time
+        let time = match &result.error {
+            Some(error) => error.clone(),
+            None => format!("{:.2?}", result.time),
+        };
+        writeln!(
+            table,
+            "{:>3} {:>4}  {:<w$}  {:<w$}  {:<6}  {}",
+            result.day,
+            result.part,
+            result.answer.to_string(),
+            result.expected.as_deref().unwrap_or("-"),
+            status,
+            time,
+            w = answer_width
+        )
+        .unwrap();
+    }
+    let count = |status| results.iter().filter(|r| r.status() == status).count();
+    let total: Duration = results.iter().map(|result| result.time).sum();
+    write!(
+        table,
+        "{} passed, {} failed, {} unchecked",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Unchecked),
+    )
+    .unwrap();
+    if count(Status::Error) > 0 {
+        write!(table, ", {} errors", count(Status::Error)).unwrap();
+    }
+    write!(table, " in {:.2?}", total).unwrap();
+    table
+}
+
+#[cfg(test)]
+mod manifest {
+    use super::*;
+    use crate::get_day;
+    use crate::input::get_input::resolve_input_dir;
+    use std::fs;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest =
+            Manifest::parse("[day7]\npart1 = 16076\n\n[day11]\npart2 = \"cqkaabcc\"").unwrap();
+        assert_eq!(manifest.expected(7, 1), Some("16076"));
+        assert_eq!(manifest.expected(7, 2), None);
+        assert_eq!(manifest.expected(11, 2), Some("cqkaabcc"));
+
+        assert!(Manifest::parse("[seven]\npart1 = 1").is_err());
+        assert!(Manifest::parse("[day7]\npart3 = 1").is_err());
+        assert!(Manifest::parse("[day7]\npart1 = 1.5").is_err());
+    }
+
+    #[test]
+    fn test_verify_day() {
+        let dir = resolve_input_dir(None);
+        let manifest = Manifest::parse("[day7]\npart1 = 16076\npart2 = 1").unwrap();
+        let results = verify_day(get_day(7).unwrap(), &dir, &manifest).unwrap();
+        let statuses: Vec<_> = results.iter().map(PartResult::status).collect();
+        assert_eq!(statuses, [Status::Pass, Status::Fail]);
+
+        let report = report(&results);
+        assert!(report.contains("  7    2  2797"));
+        assert!(report.ends_with(&format!(
+            "1 passed, 1 failed, 0 unchecked in {:.2?}",
+            results[0].time + results[1].time
+        )));
+    }
+
+    #[test]
+    fn test_bundled_manifest_covers_every_day() {
+        let manifest = Manifest::load(&resolve_input_dir(None)).unwrap();
+        for day in &DAYS {
+            assert!(manifest.expected(day.day, 1).is_some());
+        }
+    }
+
+    #[test]
+    fn test_verify_keeps_going_after_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let inputs = resolve_input_dir(None);
+        fs::copy(inputs.join(MANIFEST), dir.path().join(MANIFEST)).unwrap();
+        fs::copy(inputs.join("secret.txt"), dir.path().join("secret.txt")).unwrap();
+        fs::write(dir.path().join("strings.txt"), "").unwrap();
+        fs::write(dir.path().join("instructions.txt"), "toggle 0,0 thru 1,1\n").unwrap();
+
+        let days = [3, 5, 6].map(|day| get_day(day).unwrap());
+        let results = verify_days(&days, dir.path()).unwrap();
+        let statuses: Vec<_> = results.iter().map(PartResult::status).collect();
+        assert_eq!(
+            statuses,
+            [
+                Status::Error,
+                Status::Error,
+                Status::Fail,
+                Status::Fail,
+                Status::Error,
+                Status::Error
+            ]
+        );
+        assert!(results[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("house_directions.txt"));
+        assert!(results[4]
+            .error
+            .as_ref()
+            .unwrap()
+            .starts_with("Day 6: line 1"));
+
+        let report = report(&results);
+        assert!(report.contains("  5    1  0"));
+        assert!(report.contains("ERROR   Day 6: line 1, column 12"));
+        assert!(report.ends_with(&format!(
+            "0 passed, 2 failed, 0 unchecked, 4 errors in {:.2?}",
+            results[2].time + results[3].time
+        )));
+    }
+}